@@ -8,7 +8,7 @@ extern "C" {
 /// Aloca um array estático
 pub fn new<T: Default>(len: usize) -> Box<[T]> {
     let mut arr = unsafe {
-        Box::from_raw(std::slice::from_raw_parts_mut(
+        Box::from_raw(std::ptr::slice_from_raw_parts_mut(
             calloc(len, std::mem::size_of::<T>()) as *mut _,
             len,
         ))
@@ -0,0 +1,768 @@
+//! Backtracking que coloca as peças, um a um, na primeira posição vazia do tabuleiro
+
+use super::array;
+use super::constraints::Constraints;
+use super::control::{Checkpoint, Control, Stop};
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::{Pieces, Placement, Poliomino};
+use super::table::{self, dim, idx, Table, Topology};
+use super::trace::{Event, NoTrace, Tracer};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// Estratégia de escolha do próximo passo do backtracking
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Strategy {
+    /// Cobre o primeiro quadrado vazio (linha a linha), tentando as peças na ordem
+    #[default]
+    First,
+    /// Cobre o quadrado vazio com menos colocações possíveis, desistindo se algum não tem
+    /// nenhuma
+    Mrv,
+    /// Cobre o primeiro quadrado vazio, tentando antes as peças com menos colocações possíveis
+    /// no tabuleiro e desistindo se uma peça que deve ser usada não tem nenhuma
+    Rarest,
+}
+
+impl Strategy {
+    /// Nome da estratégia, como na opção --strategy
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::First => "first",
+            Strategy::Mrv => "mrv",
+            Strategy::Rarest => "rarest",
+        }
+    }
+
+    /// Estratégia com o nome dado (ver [Strategy::name])
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        [Strategy::First, Strategy::Mrv, Strategy::Rarest]
+            .iter()
+            .find(|s| s.name() == name)
+            .copied()
+    }
+}
+
+/// Estado do backtracking (salvo nos checkpoints, ver [crate::control])
+#[derive(Clone, Debug, Default)]
+pub(crate) struct State {
+    /// Posição em `order` da peça sendo tentada
+    pub(crate) i: usize,
+    /// Índice da transformação da peça
+    pub(crate) t_idx: usize,
+    /// Quadrado da transformação que cobre `pos` (ver [cell])
+    pub(crate) k: usize,
+    /// Quadrado do tabuleiro que deve ser coberto neste nível
+    pub(crate) pos: (i32, i32),
+    /// Peça colocada no tabuleiro, se `inserted`
+    pub(crate) placed: Placement,
+    /// Indica se a peça foi colocada no tabuleiro
+    pub(crate) inserted: bool,
+    /// Índices das peças na ordem em que são tentadas (vazio: a ordem de `pieces`)
+    pub(crate) order: Box<[usize]>,
+}
+
+impl State {
+    /// Índice da peça sendo tentada
+    fn piece(&self) -> usize {
+        if self.order.is_empty() {
+            self.i
+        } else {
+            self.order[self.i]
+        }
+    }
+}
+
+/// Dados da busca que não mudam durante o backtracking
+#[derive(Clone, Copy)]
+struct Context<'a> {
+    pieces: &'a Pieces,
+    constraints: &'a Constraints,
+    /// Indica que a busca deve parar (usado para interromper as outras threads)
+    stop: &'a AtomicBool,
+    strategy: Strategy,
+    /// Todas as cópias do inventário devem ser usadas
+    exact: bool,
+    topology: Topology,
+}
+
+/// O k-ésimo quadrado do poliominó: (0, 0) se k = 0, senão pt\[k - 1\]
+fn cell(p: &Poliomino, k: usize) -> (i32, i32) {
+    if k == 0 {
+        (0, 0)
+    } else {
+        p.pt[k - 1]
+    }
+}
+
+/// Checa se a colocação cabe no tabuleiro e é permitida pelas restrições. A posição da peça
+/// pode estar fora do tabuleiro, se a topologia der a volta nela
+fn fits(table: &Table, ctx: Context, placement: &Placement) -> bool {
+    let Placement { p_idx, t_idx, pos } = *placement;
+    let Some(pos) = ctx.topology.wrap(pos, dim(table)) else {
+        return false;
+    };
+    ctx.pieces[p_idx][t_idx].fits_in(pos.0, pos.1, table, ctx.topology)
+        && ctx
+            .constraints
+            .allows(&Placement { pos, ..*placement }, ctx.pieces, ctx.topology)
+}
+
+/// Conta as colocações possíveis que cobrem o quadrado (x, y), parando ao chegar em `limit`
+fn covering(
+    table: &Table,
+    ctx: Context,
+    left: &[usize],
+    (x, y): (i32, i32),
+    limit: usize,
+) -> usize {
+    let mut n = 0;
+    for (p_idx, transformations) in ctx.pieces.iter().enumerate() {
+        if left[p_idx] == 0 {
+            continue;
+        }
+        for (t_idx, p) in transformations.iter().enumerate() {
+            for k in 0..p.size() {
+                let (dx, dy) = cell(p, k);
+                let placement = Placement {
+                    p_idx,
+                    t_idx,
+                    pos: (x - dx, y - dy),
+                };
+                if fits(table, ctx, &placement) {
+                    n += 1;
+                    if n >= limit {
+                        return n;
+                    }
+                }
+            }
+        }
+    }
+    n
+}
+
+/// Devolve o quadrado vazio com menos colocações possíveis (None se algum não tem nenhuma)
+fn min_cell(table: &Table, ctx: Context, left: &[usize]) -> Option<(i32, i32)> {
+    let (lin, col) = table::dim(table);
+    let mut best = ((0, 0), usize::MAX);
+    for x in 0..lin {
+        for y in 0..col {
+            if table[idx(x)][idx(y)] != ' ' {
+                continue;
+            }
+            let n = covering(table, ctx, left, (x, y), best.1);
+            if n == 0 {
+                return None;
+            }
+            if n < best.1 {
+                best = ((x, y), n);
+            }
+        }
+    }
+    Some(best.0)
+}
+
+/// Devolve as peças disponíveis em ordem crescente do número de colocações possíveis no
+/// tabuleiro, sem as que não têm nenhuma (None se uma delas deve ser usada)
+fn rarest(table: &Table, ctx: Context, left: &[usize]) -> Option<Box<[usize]>> {
+    let (lin, col) = table::dim(table);
+    let mut count = array::new::<usize>(ctx.pieces.len());
+    let mut available: Pilha<usize> = Pilha::new();
+    for (p_idx, transformations) in ctx.pieces.iter().enumerate() {
+        if left[p_idx] == 0 {
+            continue;
+        }
+        for t_idx in 0..transformations.len() {
+            for x in 0..lin {
+                for y in 0..col {
+                    let placement = Placement {
+                        p_idx,
+                        t_idx,
+                        pos: (x, y),
+                    };
+                    if fits(table, ctx, &placement) {
+                        count[p_idx] += 1;
+                    }
+                }
+            }
+        }
+        if count[p_idx] > 0 {
+            available.push(p_idx);
+        } else if ctx.exact {
+            return None;
+        }
+    }
+    let mut order = available.to_array();
+    order.sort_by_key(|&p_idx| count[p_idx]);
+    Some(order)
+}
+
+/// Escolhe, segundo a estratégia, o quadrado que o próximo nível da busca deve cobrir e a ordem
+/// das peças. Os quadrados antes de `from` (linha a linha) devem estar ocupados.
+/// Devolve None se a estratégia detectar que o tabuleiro não tem solução
+fn branch(table: &Table, ctx: Context, left: &[usize], from: (i32, i32)) -> Option<State> {
+    let mut state = State::default();
+    match ctx.strategy {
+        Strategy::First => state.pos = table::next_empty(from.0, from.1, table),
+        Strategy::Mrv => state.pos = min_cell(table, ctx, left)?,
+        Strategy::Rarest => {
+            state.pos = table::next_empty(from.0, from.1, table);
+            state.order = rarest(table, ctx, left)?;
+        }
+    }
+    Some(state)
+}
+
+pub(crate) fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Devolve o mdc e o mínimo dos tamanhos das peças que ainda têm cópias disponíveis
+pub fn sizes_left(pieces: &Pieces, left: &[usize]) -> (usize, usize) {
+    let (mut g, mut min) = (0, usize::MAX);
+    for (p, &copies) in pieces.iter().zip(left.iter()) {
+        if copies > 0 {
+            g = gcd(g, p[0].size());
+            min = min.min(p[0].size());
+        }
+    }
+    (g, min)
+}
+
+/// Poda pelas componentes conexas de quadrados vazios do tabuleiro, com memória reaproveitada
+/// entre os nós da busca
+struct Pruning {
+    /// mark\[x\]\[y\] == stamp se (x, y) já foi visitado na verificação atual
+    mark: Box<[Box<[u32]>]>,
+    stamp: u32,
+    /// Pilha da busca em profundidade
+    stack: Pilha<(i32, i32)>,
+    /// Quadrados da componente visitada
+    region: Pilha<(i32, i32)>,
+}
+
+impl Pruning {
+    fn new(table: &Table) -> Pruning {
+        let mut mark = array::new::<Box<[u32]>>(table.len());
+        for line in mark.iter_mut() {
+            *line = array::new(table[0].len());
+        }
+        Pruning {
+            mark,
+            stamp: 0,
+            stack: Pilha::new(),
+            region: Pilha::new(),
+        }
+    }
+
+    /// Guarda em `region` a componente de quadrados vazios que contém (x, y), se ela ainda não
+    /// foi visitada nesta verificação. Devolve false se já foi
+    fn visit(&mut self, x: i32, y: i32, table: &Table, topology: Topology) -> bool {
+        let grid = topology.grid;
+        if table[idx(x)][idx(y)] != ' ' || self.mark[idx(x)][idx(y)] == self.stamp {
+            return false;
+        }
+        self.region.clear();
+        self.stack.push((x, y));
+        self.mark[idx(x)][idx(y)] = self.stamp;
+        while !self.stack.empty() {
+            let (cx, cy) = *self.stack.back();
+            self.stack.pop();
+            self.region.push((cx, cy));
+            for &(dx, dy) in grid.neighbours((cx, cy)) {
+                let Some((px, py)) = topology.wrap((cx + dx, cy + dy), dim(table)) else {
+                    continue;
+                };
+                if table[idx(px)][idx(py)] == ' ' && self.mark[idx(px)][idx(py)] != self.stamp {
+                    self.mark[idx(px)][idx(py)] = self.stamp;
+                    self.stack.push((px, py));
+                }
+            }
+        }
+        true
+    }
+
+    /// Checa se a componente em `region` pode ser ocupada pelas peças restantes: deve ter ao
+    /// menos `min` quadrados e ser múltipla de `g` (o mdc dos tamanhos das peças) e, se couber
+    /// só uma peça, ter a forma de uma das peças restantes
+    fn fillable(
+        &self,
+        table: &Table,
+        ctx: Context,
+        left: &[usize],
+        (g, min): (usize, usize),
+    ) -> bool {
+        let cnt = self.region.size();
+        if !cnt.is_multiple_of(g) || cnt < min {
+            return false;
+        }
+        // Cada peça tem ao menos `min` quadrados, então com menos de 2 * min quadrados a
+        // componente é ocupada por uma única peça e deve ter a sua forma. Com mais quadrados a
+        // forma não é verificada (a componente é aceita), o que nunca descarta uma solução
+        let single_piece = cnt < 2 * min;
+        if !single_piece {
+            return true;
+        }
+        // A peça deve cobrir o primeiro quadrado da componente com o seu quadrado (0, 0), a
+        // menos que a peça possa dar a volta no tabuleiro
+        let &(x, y) = self.region.iter().min().unwrap();
+        ctx.pieces
+            .iter()
+            .enumerate()
+            .any(|(p_idx, transformations)| {
+                left[p_idx] > 0
+                    && transformations[0].size() == cnt
+                    && transformations.iter().enumerate().any(|(t_idx, p)| {
+                        let cells = if ctx.topology.wraps() { p.size() } else { 1 };
+                        (0..cells).any(|k| {
+                            let (dx, dy) = cell(p, k);
+                            let placement = Placement {
+                                p_idx,
+                                t_idx,
+                                pos: (x - dx, y - dy),
+                            };
+                            fits(table, ctx, &placement)
+                        })
+                    })
+            })
+    }
+
+    /// Verifica se todas as componentes conexas de quadrados vazios do tabuleiro podem ser
+    /// ocupadas pelas peças restantes (ver [Pruning::fillable]), diminuindo o número de estados
+    /// do backtracking que iremos passar.
+    /// Se o tabuleiro antes de colocar a peça `placed` já foi verificado com as mesmas peças
+    /// restantes, só as componentes vizinhas a ela podem ter mudado, e apenas elas são visitadas
+    fn good_table(
+        &mut self,
+        table: &Table,
+        ctx: Context,
+        left: &[usize],
+        placed: Option<&Placement>,
+    ) -> bool {
+        if self.stamp == u32::MAX {
+            for line in self.mark.iter_mut() {
+                for m in line.iter_mut() {
+                    *m = 0;
+                }
+            }
+            self.stamp = 0;
+        }
+        self.stamp += 1;
+        let sizes = sizes_left(ctx.pieces, left);
+        match placed {
+            None => {
+                let (lin, col) = table::dim(table);
+                for x in 0..lin {
+                    for y in 0..col {
+                        if self.visit(x, y, table, ctx.topology)
+                            && !self.fillable(table, ctx, left, sizes)
+                        {
+                            return false;
+                        }
+                    }
+                }
+            }
+            Some(&Placement { p_idx, t_idx, pos }) => {
+                let p = &ctx.pieces[p_idx][t_idx];
+                let grid = ctx.topology.grid;
+                for k in 0..p.size() {
+                    let (dx, dy) = cell(p, k);
+                    let (x, y) = (pos.0 + dx, pos.1 + dy);
+                    for &(ex, ey) in grid.neighbours((x, y)) {
+                        let Some((nx, ny)) = ctx.topology.wrap((x + ex, y + ey), dim(table)) else {
+                            continue;
+                        };
+                        if self.visit(nx, ny, table, ctx.topology)
+                            && !self.fillable(table, ctx, left, sizes)
+                        {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Checa se todas as componentes conexas de quadrados vazios do tabuleiro podem ser ocupadas
+/// pelas peças do inventário (a mesma poda feita na raiz da busca)
+pub fn good_table(
+    table: &Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    topology: Topology,
+) -> bool {
+    let constraints = Constraints::none(pieces);
+    let stop = AtomicBool::new(false);
+    let ctx = Context {
+        pieces,
+        constraints: &constraints,
+        stop: &stop,
+        strategy: Strategy::First,
+        exact: inventory.exact,
+        topology,
+    };
+    Pruning::new(table).good_table(table, ctx, &inventory.counts, None)
+}
+
+/// Backtracking que preenche o tabuleiro usando as peças do inventário, apenas nas colocações
+/// permitidas pelas restrições.
+/// Cada vez que o tabuleiro é totalmente preenchido, chama `found` com o tabuleiro resolvido e
+/// as colocações das peças; a busca continua enquanto `found` devolver true. Cada passo da busca
+/// é passado para `tracer` (use [NoTrace] para não rastrear), e `control` limita a busca e
+/// guarda o checkpoint se ela parar antes do fim (ver [crate::control]).
+/// Devolve o número de nós visitados
+#[allow(clippy::too_many_arguments)]
+pub fn search<R: Tracer + ?Sized>(
+    table: &mut Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    constraints: &Constraints,
+    topology: Topology,
+    strategy: Strategy,
+    tracer: &mut R,
+    control: Option<&mut Control>,
+    mut found: impl FnMut(&Table, &[Placement]) -> bool,
+) -> u64 {
+    let mut left = inventory.counts.clone();
+    let stop = AtomicBool::new(false);
+    run(
+        table,
+        Context {
+            pieces,
+            constraints,
+            stop: &stop,
+            strategy,
+            exact: inventory.exact,
+            topology,
+        },
+        &mut left,
+        usize::MAX,
+        &mut |_| {},
+        tracer,
+        control,
+        &mut found,
+    )
+}
+
+/// Backtracking a partir do tabuleiro, em que `left` guarda quantas cópias de cada peça ainda
+/// podem ser usadas.
+/// Se a pilha atingir `depth` peças colocadas, em vez de descer chama `split` com as colocações
+/// feitas até então. A busca para quando `found` devolve false ou `ctx.stop` se torna verdadeiro.
+/// Os passos são passados para `tracer`, com o número de níveis na pilha. Com `control`, a busca
+/// pode começar de um checkpoint e, se parar antes do fim, guarda nele o seu estado.
+/// Devolve o número de nós visitados (peças colocadas)
+#[allow(clippy::too_many_arguments)]
+fn run<R: Tracer + ?Sized>(
+    table: &mut Table,
+    ctx: Context,
+    left: &mut [usize],
+    depth: usize,
+    split: &mut dyn FnMut(&Pilha<State>),
+    tracer: &mut R,
+    mut control: Option<&mut Control>,
+    found: &mut dyn FnMut(&Table, &[Placement]) -> bool,
+) -> u64 {
+    let Context {
+        pieces,
+        constraints,
+        stop,
+        strategy,
+        topology,
+        ..
+    } = ctx;
+    let mut nodes = 0;
+    let mut pruning = Pruning::new(table);
+    let mut state: Pilha<State> = Pilha::new();
+    match control.as_mut().and_then(|c| c.resume.take()) {
+        Some(checkpoint) => {
+            *table = checkpoint.table;
+            left.copy_from_slice(&checkpoint.left);
+            pruning.mark = checkpoint.mark;
+            pruning.stamp = checkpoint.stamp;
+            nodes = checkpoint.nodes;
+            for s in checkpoint.stack.iter() {
+                state.push(s.clone());
+            }
+        }
+        None => {
+            if table::empty_spaces(table) == 0 {
+                found(table, &[]);
+                return 0;
+            }
+            if !pruning.good_table(table, ctx, left, None) {
+                return nodes;
+            }
+            match branch(table, ctx, left, (0, 0)) {
+                Some(root) => state.push(root),
+                None => return nodes,
+            }
+        }
+    }
+    // Contar quantos quadrados ainda estão vazios
+    let mut count = table::empty_spaces(table);
+    let start_nodes = nodes;
+
+    // Loop da backtracking
+    while !state.empty() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Some(control) = control.as_mut() {
+            if control.check(nodes - start_nodes, state.size()) {
+                break;
+            }
+        }
+        let level = state.size();
+        let depth_reached = level >= depth;
+        let top = state.back();
+        let options = if top.order.is_empty() {
+            pieces.len()
+        } else {
+            top.order.len()
+        };
+        if top.inserted {
+            let Placement { p_idx, t_idx, pos } = top.placed;
+            let p = &pieces[p_idx][t_idx];
+            p.remove(pos.0, pos.1, table, topology);
+            top.inserted = false;
+            left[p_idx] += 1;
+            count += p.size();
+            top.k += 1;
+            tracer.trace(Event::Remove(top.placed), level, table);
+            continue;
+        } else if top.i >= options {
+            let pos = top.pos;
+            state.pop();
+            tracer.trace(Event::Pop(pos), level, table);
+            continue;
+        }
+        let p_idx = top.piece();
+        // Na estratégia Mrv qualquer quadrado da peça pode cobrir `pos`; nas outras os
+        // quadrados anteriores a `pos` estão ocupados, então apenas o quadrado (0, 0), a menos
+        // que a peça possa dar a volta no tabuleiro
+        let cells = |p: &Poliomino| {
+            if strategy == Strategy::Mrv || topology.wraps() {
+                p.size()
+            } else {
+                1
+            }
+        };
+        if left[p_idx] == 0 {
+            top.i += 1;
+        } else if top.t_idx >= pieces[p_idx].len() {
+            top.i += 1;
+            top.t_idx = 0;
+        } else if top.k >= cells(&pieces[p_idx][top.t_idx]) {
+            top.t_idx += 1;
+            top.k = 0;
+        } else {
+            let p = &pieces[p_idx][top.t_idx];
+            let (dx, dy) = cell(p, top.k);
+            let Some((x, y)) = topology.wrap((top.pos.0 - dx, top.pos.1 - dy), dim(table)) else {
+                top.k += 1;
+                continue;
+            };
+            let placement = Placement {
+                p_idx,
+                t_idx: top.t_idx,
+                pos: (x, y),
+            };
+            if !constraints.allows(&placement, pieces, topology) {
+                top.k += 1;
+                continue;
+            }
+            if p.try_put(x, y, table, topology) {
+                top.inserted = true;
+                top.placed = placement;
+                let pos = top.pos;
+                left[p_idx] -= 1;
+                count -= p.size();
+                nodes += 1;
+                tracer.trace(Event::Put(placement), level, table);
+                if count == 0 {
+                    tracer.trace(Event::Solution, level, table);
+                    // A peça é removida na próxima iteração, seguindo a busca
+                    if !found(table, &placements(&state)) {
+                        if let Some(control) = control.as_mut() {
+                            control.stop = Some(Stop::Found);
+                        }
+                        break;
+                    }
+                } else if pruning.good_table(
+                    table,
+                    ctx,
+                    left,
+                    // Se a peça acabou, as peças restantes mudaram
+                    if left[p_idx] > 0 {
+                        Some(&placement)
+                    } else {
+                        None
+                    },
+                ) {
+                    if depth_reached {
+                        split(&state);
+                    } else if let Some(next) = branch(table, ctx, left, pos) {
+                        state.push(next);
+                    }
+                } else {
+                    tracer.trace(Event::Prune(placement), level, table);
+                }
+            } else {
+                top.k += 1;
+                tracer.trace(Event::Fail(placement), level, table);
+            }
+        }
+    }
+    if let Some(control) = control {
+        if !state.empty() {
+            // A busca parou antes do fim: o laço recomeça exatamente deste estado
+            let mut stack = array::new::<State>(state.size());
+            for (i, s) in state.iter().enumerate() {
+                stack[i] = s.clone();
+            }
+            control.checkpoint = Some(Checkpoint {
+                nodes,
+                left: left.into(),
+                table: table.clone(),
+                mark: pruning.mark.clone(),
+                stamp: pruning.stamp,
+                stack,
+                ..Checkpoint::default()
+            });
+        }
+    }
+    nodes
+}
+
+/// Devolve as colocações das peças da pilha da backtracking
+fn placements(state: &Pilha<State>) -> Box<[Placement]> {
+    let mut arr = array::new::<Placement>(state.size());
+    for (i, s) in state.iter().enumerate() {
+        arr[i] = s.placed;
+    }
+    arr
+}
+
+/// Backtracking paralelo com `threads` threads.
+/// Os primeiros níveis da árvore de busca são explorados sequencialmente, gerando uma tarefa
+/// para cada subárvore, e as tarefas são divididas entre as threads conforme elas terminam as
+/// anteriores. Cada thread acumula o que encontra em seu próprio `T`, passado para `found`
+/// junto com cada solução (e as colocações das peças); se alguma chamada de `found` devolver
+/// false, todas as threads param.
+/// Devolve o acumulado de cada thread (o último é o das soluções encontradas nos primeiros
+/// níveis) e o número de nós visitados.
+#[allow(clippy::too_many_arguments)]
+pub fn parallel_search<T: Default + Send>(
+    table: &Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    constraints: &Constraints,
+    topology: Topology,
+    strategy: Strategy,
+    threads: usize,
+    found: &(dyn Fn(&Table, &[Placement], &mut T) -> bool + Sync),
+) -> (Box<[T]>, u64) {
+    let mut results = array::new::<T>(threads + 1);
+    let stop = AtomicBool::new(false);
+    let nodes = AtomicU64::new(0);
+    let ctx = Context {
+        pieces,
+        constraints,
+        stop: &stop,
+        strategy,
+        exact: inventory.exact,
+        topology,
+    };
+
+    // Escolher a profundidade em que a árvore é dividida, até termos tarefas o bastante
+    const MAX_DEPTH: usize = 3;
+    let mut depth = 1;
+    while depth < MAX_DEPTH {
+        let mut tasks = 0;
+        run(
+            &mut table.clone(),
+            ctx,
+            &mut inventory.counts.clone(),
+            depth,
+            &mut |_| tasks += 1,
+            &mut NoTrace,
+            None,
+            &mut |_, _| true,
+        );
+        if tasks >= 8 * threads {
+            break;
+        }
+        depth += 1;
+    }
+
+    let mut tasks: Pilha<Box<[Placement]>> = Pilha::new();
+    let first = &mut results[threads];
+    let prefix_nodes = run(
+        &mut table.clone(),
+        ctx,
+        &mut inventory.counts.clone(),
+        depth,
+        &mut |state| tasks.push(placements(state)),
+        &mut NoTrace,
+        None,
+        &mut |solution, placements| {
+            let keep_going = found(solution, placements, first);
+            if !keep_going {
+                stop.store(true, Ordering::Relaxed);
+            }
+            keep_going
+        },
+    );
+    let tasks = tasks.to_array();
+    nodes.fetch_add(prefix_nodes, Ordering::Relaxed);
+
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for result in results[..threads].iter_mut() {
+            let (tasks, next, stop, nodes) = (&tasks, &next, &stop, &nodes);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= tasks.len() || stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let mut table = table.clone();
+                let mut left = inventory.counts.clone();
+                for &Placement { p_idx, t_idx, pos } in tasks[i].iter() {
+                    assert!(
+                        pieces[p_idx][t_idx].try_put(pos.0, pos.1, &mut table, topology),
+                        "Tarefa com colocação inválida"
+                    );
+                    left[p_idx] -= 1;
+                }
+                let task_nodes = run(
+                    &mut table,
+                    ctx,
+                    &mut left,
+                    usize::MAX,
+                    &mut |_| {},
+                    &mut NoTrace,
+                    None,
+                    &mut |solution, placements| {
+                        // As colocações da tarefa vêm antes das feitas nesta busca
+                        let mut all = array::new::<Placement>(tasks[i].len() + placements.len());
+                        all[..tasks[i].len()].copy_from_slice(&tasks[i]);
+                        all[tasks[i].len()..].copy_from_slice(placements);
+                        let keep_going = found(solution, &all, result);
+                        if !keep_going {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                        keep_going
+                    },
+                );
+                nodes.fetch_add(task_nodes, Ordering::Relaxed);
+            });
+        }
+    });
+    (results, nodes.into_inner())
+}
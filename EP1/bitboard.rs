@@ -0,0 +1,297 @@
+//! Backtracking com o tabuleiro representado por bits (bitboard)
+//!
+//! Cada quadrado do tabuleiro é um bit de um `u128`, e cada maneira de colocar uma peça é
+//! pré-calculada como a máscara dos quadrados que ela ocupa. Assim checar se a peça cabe é um
+//! AND, colocar e tirar a peça é um XOR e as componentes conexas de quadrados vazios são
+//! encontradas deslocando máscaras. A ordem da busca é a mesma do [backtrack](super::backtrack)
+//! com a estratégia `First`, então as soluções são encontradas na mesma ordem.
+
+use super::array;
+use super::backtrack::sizes_left;
+use super::constraints::Constraints;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::{Pieces, Placement};
+use super::table::{dim, idx, Table, Topology};
+
+/// Conjunto de quadrados do tabuleiro: o quadrado (x, y) é o bit x * col + y
+type Mask = u128;
+
+/// Número máximo de quadrados do tabuleiro
+pub const MAX_CELLS: usize = Mask::BITS as usize;
+
+/// Checa se o tabuleiro cabe em uma máscara
+pub fn supports(table: &Table) -> bool {
+    let (lin, col) = dim(table);
+    idx(lin * col) <= MAX_CELLS
+}
+
+/// Máscaras fixas do tabuleiro
+struct Board {
+    lin: i32,
+    col: i32,
+    topology: Topology,
+    /// Todos os quadrados
+    all: Mask,
+    /// Quadrados fora da primeira coluna
+    not_first: Mask,
+    /// Quadrados fora da última coluna
+    not_last: Mask,
+    /// Quadrados da primeira coluna
+    first_col: Mask,
+    /// Quadrados da última coluna
+    last_col: Mask,
+    /// Quadrados da primeira linha
+    first_line: Mask,
+    /// Quadrados da última linha
+    last_line: Mask,
+}
+
+impl Board {
+    fn new(lin: i32, col: i32, topology: Topology) -> Board {
+        let mut board = Board {
+            lin,
+            col,
+            topology,
+            all: 0,
+            not_first: 0,
+            not_last: 0,
+            first_col: 0,
+            last_col: 0,
+            first_line: 0,
+            last_line: 0,
+        };
+        for x in 0..lin {
+            for y in 0..col {
+                let b = board.bit(x, y);
+                board.all |= b;
+                if y > 0 {
+                    board.not_first |= b;
+                } else {
+                    board.first_col |= b;
+                }
+                if y + 1 < col {
+                    board.not_last |= b;
+                } else {
+                    board.last_col |= b;
+                }
+                if x == 0 {
+                    board.first_line |= b;
+                }
+                if x + 1 == lin {
+                    board.last_line |= b;
+                }
+            }
+        }
+        board
+    }
+
+    /// Máscara do quadrado (x, y)
+    fn bit(&self, x: i32, y: i32) -> Mask {
+        1 << (x * self.col + y)
+    }
+
+    /// Junta à região os quadrados vizinhos a ela
+    fn grow(&self, region: Mask) -> Mask {
+        let mut next = region
+            | (region & self.not_last) << 1
+            | (region & self.not_first) >> 1
+            | region << self.col
+            | region >> self.col;
+        // Vizinhos do outro lado das bordas ligadas
+        if self.topology.wrap_columns {
+            next |= (region & self.last_col) >> (self.col - 1)
+                | (region & self.first_col) << (self.col - 1);
+        }
+        if self.topology.wrap_lines {
+            let shift = (self.lin - 1) * self.col;
+            next |= (region & self.last_line) >> shift | (region & self.first_line) << shift;
+        }
+        next & self.all
+    }
+
+    /// Verifica se todas as componentes conexas de `empty` têm ao menos `min` quadrados e são
+    /// múltiplas de `g`, e se as componentes em que só cabe uma peça têm a forma de uma das
+    /// peças restantes, isto é, são a máscara de uma das colocações (ver
+    /// [good_table](super::backtrack))
+    fn good_regions(
+        &self,
+        empty: Mask,
+        options: &[Box<[Choice]>],
+        left: &[usize],
+        (g, min): (usize, usize),
+    ) -> bool {
+        let mut rest = empty;
+        while rest != 0 {
+            // Cresce a região a partir do menor quadrado vazio até não mudar mais
+            let mut region = rest & rest.wrapping_neg();
+            loop {
+                let next = self.grow(region) & rest;
+                if next == region {
+                    break;
+                }
+                region = next;
+            }
+            let cnt = region.count_ones() as usize;
+            if !cnt.is_multiple_of(g) || cnt < min {
+                return false;
+            }
+            // Se só cabe uma peça, uma colocação deve ocupar exatamente a componente, e o seu
+            // menor quadrado é o da componente
+            if cnt < 2 * min
+                && !options[region.trailing_zeros() as usize]
+                    .iter()
+                    .any(|c| c.mask == region && left[c.placement.p_idx] > 0)
+            {
+                return false;
+            }
+            rest &= !region;
+        }
+        true
+    }
+}
+
+/// Uma maneira de colocar uma peça no tabuleiro e os quadrados que ela ocupa
+#[derive(Clone, Copy, Default)]
+struct Choice {
+    placement: Placement,
+    mask: Mask,
+}
+
+/// Nível da busca: quadrado a ser coberto e índice da colocação tentada em options\[cell\]
+#[derive(Clone, Copy, Default)]
+struct Level {
+    cell: usize,
+    i: usize,
+    inserted: bool,
+}
+
+/// Backtracking que preenche o tabuleiro (de no máximo [MAX_CELLS] quadrados) usando as peças
+/// do inventário, apenas nas colocações permitidas pelas restrições e dando a volta nas bordas
+/// ligadas pela topologia.
+/// Cada vez que o tabuleiro é totalmente preenchido, chama `found` com o tabuleiro resolvido e
+/// as colocações das peças; a busca continua enquanto `found` devolver true.
+/// Devolve o número de nós visitados (peças colocadas)
+pub fn search(
+    table: &mut Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    constraints: &Constraints,
+    topology: Topology,
+    mut found: impl FnMut(&Table, &[Placement]) -> bool,
+) -> u64 {
+    assert!(
+        supports(table),
+        "Tabuleiro com mais de {} quadrados",
+        MAX_CELLS
+    );
+    let (lin, col) = dim(table);
+    let board = Board::new(lin, col, topology);
+    let mut empty: Mask = 0;
+    for x in 0..lin {
+        for y in 0..col {
+            if table[idx(x)][idx(y)] == ' ' {
+                empty |= board.bit(x, y);
+            }
+        }
+    }
+    if empty == 0 {
+        found(table, &[]);
+        return 0;
+    }
+
+    // options[c]: colocações cujo primeiro quadrado (o menor bit da máscara) é o quadrado c.
+    // Sem bordas ligadas, esse é o quadrado (0, 0) da peça
+    let mut by_cell = array::new::<Pilha<Choice>>(idx(lin * col));
+    for (p_idx, transformations) in pieces.iter().enumerate() {
+        for (t_idx, p) in transformations.iter().enumerate() {
+            for x in 0..lin {
+                for y in 0..col {
+                    let placement = Placement {
+                        p_idx,
+                        t_idx,
+                        pos: (x, y),
+                    };
+                    if !p.fits_in(x, y, table, topology)
+                        || !constraints.allows(&placement, pieces, topology)
+                    {
+                        continue;
+                    }
+                    let mut mask = board.bit(x, y);
+                    for &(dx, dy) in p.pt.iter() {
+                        let (px, py) = topology.wrap((x + dx, y + dy), (lin, col)).unwrap();
+                        mask |= board.bit(px, py);
+                    }
+                    by_cell[mask.trailing_zeros() as usize].push(Choice { placement, mask });
+                }
+            }
+        }
+    }
+    let mut options = array::new::<Box<[Choice]>>(by_cell.len());
+    for (c, choices) in by_cell.iter().enumerate() {
+        options[c] = choices.to_array();
+    }
+
+    let mut left = inventory.counts.clone();
+    // Como no backtracking, o tabuleiro inicial também passa pela poda
+    if !board.good_regions(empty, &options, &left, sizes_left(pieces, &left)) {
+        return 0;
+    }
+    let mut nodes = 0;
+    let mut state: Pilha<Level> = Pilha::new();
+    state.push(Level {
+        cell: empty.trailing_zeros() as usize,
+        ..Level::default()
+    });
+    while !state.empty() {
+        let level = state.back();
+        if level.inserted {
+            let choice = &options[level.cell][level.i];
+            empty |= choice.mask;
+            left[choice.placement.p_idx] += 1;
+            level.inserted = false;
+            level.i += 1;
+            continue;
+        }
+        if level.i >= options[level.cell].len() {
+            state.pop();
+            continue;
+        }
+        let choice = &options[level.cell][level.i];
+        let p_idx = choice.placement.p_idx;
+        if left[p_idx] == 0 || choice.mask & !empty != 0 {
+            level.i += 1;
+            continue;
+        }
+        empty ^= choice.mask;
+        left[p_idx] -= 1;
+        level.inserted = true;
+        nodes += 1;
+        if empty == 0 {
+            // A peça é retirada na próxima iteração, seguindo a busca
+            let mut chosen = array::new::<Placement>(state.size());
+            for (i, l) in state.iter().enumerate() {
+                chosen[i] = options[l.cell][l.i].placement;
+            }
+            for &Placement { p_idx, t_idx, pos } in chosen.iter() {
+                assert!(
+                    pieces[p_idx][t_idx].try_put(pos.0, pos.1, table, topology),
+                    "Colocação do bitboard inválida"
+                );
+            }
+            let keep_going = found(table, &chosen);
+            for &Placement { p_idx, t_idx, pos } in chosen.iter() {
+                pieces[p_idx][t_idx].remove(pos.0, pos.1, table, topology);
+            }
+            if !keep_going {
+                break;
+            }
+        } else if board.good_regions(empty, &options, &left, sizes_left(pieces, &left)) {
+            state.push(Level {
+                cell: empty.trailing_zeros() as usize,
+                ..Level::default()
+            });
+        }
+    }
+    nodes
+}
@@ -0,0 +1,168 @@
+//! Gerador de tabuleiros aleatórios com solução
+//!
+//! Os tabuleiros são gerados tirando quadrados de um retângulo (`Carve`) ou crescendo uma região
+//! a partir de um quadrado (`Grow`), e só são aceitos os que têm tantos quadrados vazios quanto
+//! a área das peças, passam pela poda do backtracking e têm solução (ou solução única). Os
+//! sorteios dependem apenas da semente, então a mesma semente gera sempre o mesmo tabuleiro.
+
+use super::array;
+use super::backtrack;
+use super::grid::Grid;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::solver::{self, Options};
+use super::table::{self, idx, ok, Table};
+
+/// Gerador de números pseudoaleatórios (xorshift64*)
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // Espalha os bits da semente (splitmix64), já que o estado não pode ser 0
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Rng {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    /// Devolve o próximo número sorteado
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Sorteia um número em 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Como o tabuleiro é construído
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    /// Bloqueia quadrados de um retângulo com as dimensões dadas, sempre vizinhos à borda ou a
+    /// um quadrado já bloqueado, até sobrarem tantos quadrados quanto a área das peças
+    Carve(usize, usize),
+    /// Cresce uma região a partir de um quadrado, juntando quadrados vizinhos sorteados, e
+    /// devolve o menor retângulo que a contém
+    Grow,
+}
+
+/// Retângulo `lin` x `col` com quadrados bloqueados até sobrarem `cells` quadrados vazios
+fn carve(rng: &mut Rng, (lin, col): (usize, usize), cells: usize, grid: Grid) -> Table {
+    let mut table: Table = array::new(lin);
+    for line in table.iter_mut() {
+        *line = array::new::<char>(col);
+        for c in line.iter_mut() {
+            *c = ' ';
+        }
+    }
+    let mut empty = lin * col;
+    while empty > cells {
+        let (x, y) = (rng.below(lin) as i32, rng.below(col) as i32);
+        let border = grid.geometry().neighbours((x, y)).iter().any(|&(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            !ok(nx, ny, &table) || table[idx(nx)][idx(ny)] == '~'
+        });
+        if table[idx(x)][idx(y)] == ' ' && border {
+            table[idx(x)][idx(y)] = '~';
+            empty -= 1;
+        }
+    }
+    table
+}
+
+/// Região conexa com `cells` quadrados, no menor retângulo que a contém (com uma coluna a mais
+/// na grade triangular, se preciso para manter o tipo dos triângulos)
+fn grow(rng: &mut Rng, cells: usize, grid: Grid) -> Table {
+    // A região cabe num quadrado de lado 2 * cells - 1 centrado no primeiro quadrado
+    let side = 2 * cells - 1;
+    let mut inside = array::new::<Box<[bool]>>(side);
+    for line in inside.iter_mut() {
+        *line = array::new(side);
+    }
+    let mut region: Pilha<(i32, i32)> = Pilha::new();
+    let center = (cells - 1) as i32;
+    region.push((center, center));
+    inside[idx(center)][idx(center)] = true;
+    while region.size() < cells {
+        let (x, y) = *region.iter().nth(rng.below(region.size())).unwrap();
+        let neighbours = grid.geometry().neighbours((x, y));
+        let (dx, dy) = neighbours[rng.below(neighbours.len())];
+        let (nx, ny) = (x + dx, y + dy);
+        if !inside[idx(nx)][idx(ny)] {
+            inside[idx(nx)][idx(ny)] = true;
+            region.push((nx, ny));
+        }
+    }
+    let (x0, mut y0) = (
+        region.iter().map(|p| p.0).min().unwrap(),
+        region.iter().map(|p| p.1).min().unwrap(),
+    );
+    if grid.geometry().parity((x0, y0)) {
+        y0 -= 1;
+    }
+    let (x1, y1) = (
+        region.iter().map(|p| p.0).max().unwrap(),
+        region.iter().map(|p| p.1).max().unwrap(),
+    );
+    let mut table: Table = array::new(idx(x1 - x0 + 1));
+    for (x, line) in table.iter_mut().enumerate() {
+        *line = array::new::<char>(idx(y1 - y0 + 1));
+        for (y, c) in line.iter_mut().enumerate() {
+            let (ix, iy) = (x0 + x as i32, y0 + y as i32);
+            *c = if iy >= 0 && inside[idx(ix)][idx(iy)] {
+                ' '
+            } else {
+                '~'
+            };
+        }
+    }
+    table
+}
+
+/// Gera um tabuleiro que pode ser preenchido com as peças das opções (com solução única se
+/// `unique`), tentando no máximo `tries` tabuleiros sorteados.
+/// Devolve o tabuleiro e o número de tentativas, ou None se nenhuma tentativa serviu (ou se o
+/// inventário está vazio)
+pub fn generate(
+    opts: &Options,
+    method: Method,
+    unique: bool,
+    rng: &mut Rng,
+    tries: u64,
+) -> Option<(Table, u64)> {
+    let inventory = match &opts.inventory {
+        Some(inventory) => inventory.clone(),
+        None => Inventory::all(&opts.pieces, true),
+    };
+    let cells = inventory.area(&opts.pieces);
+    if cells == 0 {
+        return None;
+    }
+    for attempt in 1..=tries {
+        let table = match method {
+            Method::Carve(lin, col) => carve(rng, (lin, col), cells, opts.topology.grid),
+            Method::Grow => grow(rng, cells, opts.topology.grid),
+        };
+        if !inventory.fits(&opts.pieces, table::empty_spaces(&table))
+            || !backtrack::good_table(&table, &opts.pieces, &inventory, opts.topology)
+        {
+            continue;
+        }
+        let limit = if unique { 2 } else { 1 };
+        if let Ok((found, _)) = solver::distinct(&table, opts, limit) {
+            if found.len() == 1 {
+                return Some((table, attempt));
+            }
+        }
+    }
+    None
+}
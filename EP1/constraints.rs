@@ -0,0 +1,235 @@
+//! Restrições de colocação: em quais quadrados e em quais transformações cada peça pode ser
+//! colocada
+
+use super::array;
+use super::poliomino::{Pieces, Placement};
+use super::table::{dim, idx, ok, Table, Topology};
+
+/// Quadrados permitidos: mask\[x\]\[y\] indica se (x, y) pode ser ocupado
+type Mask = Box<[Box<[bool]>]>;
+
+/// cells\[i\]: quadrados do tabuleiro que a i-ésima peça pode ocupar (`None`: todos).
+/// orientations\[i\]: transformações da i-ésima peça que podem ser usadas (`None`: todas).
+/// anchors\[i\]\[t\]: posições em que a transformação t da i-ésima peça pode ser colocada
+/// (`None`: todas)
+#[derive(Clone, Default)]
+pub struct Constraints {
+    cells: Box<[Option<Mask>]>,
+    orientations: Box<[Option<Box<[bool]>>]>,
+    anchors: Box<[Option<Box<[Mask]>>]>,
+}
+
+/// Lê uma coordenada "l,c" do tabuleiro (contada a partir de 0)
+fn coordinate(s: &str, table: &Table) -> Option<(i32, i32)> {
+    let (x, y) = s.split_once(',')?;
+    let (x, y) = (x.trim().parse::<i32>().ok()?, y.trim().parse::<i32>().ok()?);
+    if ok(x, y, table) {
+        Some((x, y))
+    } else {
+        None
+    }
+}
+
+/// Checa se (x, y) está dentro da máscara e é permitido
+fn ok_mask(mask: &Mask, x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && mask.get(idx(x)).and_then(|l| l.get(idx(y))) == Some(&true)
+}
+
+/// Devolve a máscara transposta
+fn transpost(mask: &Mask) -> Mask {
+    let mut new_mask: Mask = array::new(mask[0].len());
+    for (y, line) in new_mask.iter_mut().enumerate() {
+        *line = array::new(mask.len());
+        for (x, c) in line.iter_mut().enumerate() {
+            *c = mask[x][y];
+        }
+    }
+    new_mask
+}
+
+impl Constraints {
+    /// Nenhuma restrição
+    pub fn none(pieces: &Pieces) -> Constraints {
+        Constraints {
+            cells: array::new(pieces.len()),
+            orientations: array::new(pieces.len()),
+            anchors: array::new(pieces.len()),
+        }
+    }
+
+    /// Permite que a peça p_idx seja colocada apenas nas colocações dadas
+    pub fn allow_only(
+        &mut self,
+        p_idx: usize,
+        placements: &[Placement],
+        pieces: &Pieces,
+        table: &Table,
+    ) {
+        let (lin, col) = dim(table);
+        let mut anchors = array::new::<Mask>(pieces[p_idx].len());
+        for mask in anchors.iter_mut() {
+            *mask = array::new(idx(lin));
+            for line in mask.iter_mut() {
+                *line = array::new(idx(col));
+            }
+        }
+        for &Placement { t_idx, pos, .. } in placements.iter() {
+            anchors[t_idx][idx(pos.0)][idx(pos.1)] = true;
+        }
+        self.anchors[p_idx] = Some(anchors);
+    }
+
+    /// Checa se não há nenhuma restrição
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(Option::is_none)
+            && self.orientations.iter().all(Option::is_none)
+            && self.anchors.iter().all(Option::is_none)
+    }
+
+    /// Lê as restrições de um texto com uma restrição por linha, no formato
+    /// "<peça> <tipo> <argumentos>", em que o tipo é:
+    /// - `cells l,c l,c ...`: a peça pode ocupar os quadrados dados;
+    /// - `rect l1,c1 l2,c2`: a peça pode ocupar o retângulo com esses cantos;
+    /// - `orientations i,j,...`: a peça só pode ser usada nas transformações dadas (índices em
+    ///   pieces\[p_idx\]).
+    ///
+    /// Várias linhas para a mesma peça juntam os quadrados (ou as transformações) permitidos, e
+    /// uma peça só pode ser colocada se todos os seus quadrados são permitidos.
+    /// Linhas começando com // são comentários. Ex.: "X rect 2,3 3,6"
+    pub fn parse(s: &str, pieces: &Pieces, table: &Table) -> Result<Constraints, String> {
+        let mut constraints = Constraints::none(pieces);
+        let (lin, col) = dim(table);
+        for (num, line) in s.lines().enumerate() {
+            let num = num + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let (ident, kind) = (words.next().unwrap(), words.next().unwrap_or_default());
+            let p_idx = pieces
+                .iter()
+                .position(|p| ident.chars().eq(std::iter::once(p[0].ident)))
+                .ok_or_else(|| format!("Linha {num}: peça {ident} não existe"))?;
+            let invalid = |w: &str| format!("Linha {num}: argumento inválido \"{w}\"");
+            if kind == "orientations" {
+                let allowed = constraints.orientations[p_idx]
+                    .get_or_insert_with(|| array::new(pieces[p_idx].len()));
+                for w in words.flat_map(|w| w.split(',')).filter(|w| !w.is_empty()) {
+                    match w.parse::<usize>() {
+                        Ok(t_idx) if t_idx < allowed.len() => allowed[t_idx] = true,
+                        _ => return Err(invalid(w)),
+                    }
+                }
+                continue;
+            }
+            let allowed = constraints.cells[p_idx].get_or_insert_with(|| {
+                let mut cells: Mask = array::new(idx(lin));
+                for line in cells.iter_mut() {
+                    *line = array::new(idx(col));
+                }
+                cells
+            });
+            match kind {
+                "cells" => {
+                    for w in words {
+                        let (x, y) = coordinate(w, table).ok_or_else(|| invalid(w))?;
+                        allowed[idx(x)][idx(y)] = true;
+                    }
+                }
+                "rect" => {
+                    let mut corners = words.map(|w| coordinate(w, table).ok_or_else(|| invalid(w)));
+                    let (a, b) = match (corners.next(), corners.next(), corners.next()) {
+                        (Some(a), Some(b), None) => (a?, b?),
+                        _ => return Err(format!("Linha {num}: rect precisa de dois cantos")),
+                    };
+                    for x in a.0.min(b.0)..=a.0.max(b.0) {
+                        for y in a.1.min(b.1)..=a.1.max(b.1) {
+                            allowed[idx(x)][idx(y)] = true;
+                        }
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "Linha {num}: restrição \"{kind}\" inválida (use cells, rect ou \
+                         orientations)"
+                    ))
+                }
+            }
+        }
+        Ok(constraints)
+    }
+
+    /// Lê as restrições do arquivo
+    pub fn read(path: &str, pieces: &Pieces, table: &Table) -> Result<Constraints, String> {
+        let s = std::fs::read_to_string(path)
+            .map_err(|e| format!("Erro ao ler o arquivo de restrições {path}: {e}"))?;
+        Constraints::parse(&s, pieces, table)
+    }
+
+    /// Checa se a colocação é permitida (quadrados fora do tabuleiro nunca são, a menos que a
+    /// topologia dê a volta neles)
+    pub fn allows(&self, placement: &Placement, pieces: &Pieces, topology: Topology) -> bool {
+        let Placement { p_idx, t_idx, pos } = *placement;
+        if let Some(allowed) = &self.orientations[p_idx] {
+            if !allowed[t_idx] {
+                return false;
+            }
+        }
+        if let Some(anchors) = &self.anchors[p_idx] {
+            let (x, y) = pos;
+            if !ok_mask(&anchors[t_idx], x, y) {
+                return false;
+            }
+        }
+        if let Some(allowed) = &self.cells[p_idx] {
+            let (x, y) = pos;
+            let p = &pieces[p_idx][t_idx];
+            let dim = (allowed.len() as i32, allowed[0].len() as i32);
+            return ok_mask(allowed, x, y)
+                && p.pt.iter().all(|&(dx, dy)| {
+                    topology
+                        .wrap((x + dx, y + dy), dim)
+                        .is_some_and(|(x, y)| ok_mask(allowed, x, y))
+                });
+        }
+        true
+    }
+
+    /// Devolve as restrições no tabuleiro transposto, com as peças transpostas (que mantêm os
+    /// índices das transformações). `pieces` são as peças antes de transpor
+    pub fn transposed(&self, pieces: &Pieces) -> Constraints {
+        let mut result = self.clone();
+        for cells in result.cells.iter_mut().flatten() {
+            *cells = transpost(cells);
+        }
+        for (p_idx, anchors) in result.anchors.iter_mut().enumerate() {
+            let Some(anchors) = anchors else {
+                continue;
+            };
+            for (t_idx, mask) in anchors.iter_mut().enumerate() {
+                // O quadrado (0, 0) da transformação transposta é o primeiro dos seus quadrados
+                // transpostos
+                let p = &pieces[p_idx][t_idx];
+                let mut new_mask = transpost(mask);
+                for line in new_mask.iter_mut() {
+                    for c in line.iter_mut() {
+                        *c = false;
+                    }
+                }
+                for (x, line) in mask.iter().enumerate() {
+                    for (y, _) in line.iter().enumerate().filter(|(_, &c)| c) {
+                        let (x, y) = (x as i32, y as i32);
+                        let mut pos = (y, x);
+                        for &(dx, dy) in p.pt.iter() {
+                            pos = pos.min((y + dy, x + dx));
+                        }
+                        new_mask[idx(pos.0)][idx(pos.1)] = true;
+                    }
+                }
+                *mask = new_mask;
+            }
+        }
+        result
+    }
+}
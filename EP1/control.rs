@@ -0,0 +1,317 @@
+//! Controle de buscas longas: limites de nós e de tempo, interrupção (ex.: por SIGINT),
+//! relatório de progresso e checkpoints, com o estado do backtracking para continuar a busca
+//! depois exatamente de onde ela parou.
+//!
+//! O checkpoint é um arquivo de texto com uma seção por linha de cabeçalho:
+//! ```text
+//! pentaminos checkpoint
+//! pieces FILNPTUVWXYZ
+//! inventory 1 1 1 ...      (cópias de cada peça a colocar)
+//! options first 0 0        (estratégia, --any e quebra de simetria)
+//! solutions 3 1
+//! nodes 123456
+//! left 0 0 1 ...
+//! stamp 4321
+//! board 6                  (tabuleiro dado, no formato de table::format)
+//! table 6                  (tabuleiro da busca quando ela parou)
+//! mark 6                   (marcas da poda, uma linha por linha do tabuleiro)
+//! stack 5                  (um nível da pilha da busca por linha)
+//! ```
+
+use super::backtrack::{State, Strategy};
+use super::pilha::Pilha;
+use super::poliomino::Placement;
+use super::table::{self, Table};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Motivo pelo qual a busca parou antes do fim
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stop {
+    /// Atingiu o limite de nós
+    Nodes,
+    /// Atingiu o limite de tempo
+    Time,
+    /// Foi interrompida de fora (ex.: SIGINT)
+    Interrupt,
+    /// Quem recebe as soluções pediu para parar (ex.: limite de soluções)
+    Found,
+}
+
+/// Estado do backtracking salvo quando a busca para antes do fim
+#[derive(Clone, Debug, Default)]
+pub struct Checkpoint {
+    /// Identificadores das peças, para checar que a busca continua com as mesmas peças
+    pub pieces: String,
+    /// Tabuleiro dado à busca, para checar que ela continua no mesmo tabuleiro
+    pub board: Table,
+    /// Cópias de cada peça a colocar (descontadas as que já estão no tabuleiro dado), se o
+    /// inventário é apenas um limite (--any), a estratégia e se a simetria foi quebrada, para
+    /// checar que a busca continua com o mesmo inventário e as mesmas opções
+    pub inventory: Box<[usize]>,
+    pub any: bool,
+    pub strategy: Strategy,
+    pub break_symmetry: bool,
+    /// Soluções encontradas (total e distintas) antes do checkpoint, guardadas por quem chama a
+    /// busca
+    pub total: u64,
+    pub distinct: u64,
+    /// Nós visitados antes do checkpoint
+    pub nodes: u64,
+    /// Cópias restantes de cada peça
+    pub(crate) left: Box<[usize]>,
+    /// Tabuleiro da busca, com as peças da pilha colocadas
+    pub(crate) table: Table,
+    /// Marcas e contador da poda pelas componentes conexas (ver [crate::backtrack])
+    pub(crate) mark: Box<[Box<[u32]>]>,
+    pub(crate) stamp: u32,
+    /// Pilha da busca, do primeiro ao último nível
+    pub(crate) stack: Box<[State]>,
+}
+
+/// Relatório de progresso: recebe o número de nós visitados e o número de níveis na pilha
+pub type Progress<'a> = &'a mut dyn FnMut(u64, usize);
+
+/// Limites e acompanhamento da busca
+pub struct Control<'a> {
+    /// Para depois de visitar esse número de nós nesta busca
+    pub max_nodes: Option<u64>,
+    /// Para ao passar desse instante
+    pub deadline: Option<Instant>,
+    /// Para quando se tornar verdadeiro
+    pub interrupt: Option<&'a AtomicBool>,
+    /// Chamado a cada intervalo
+    pub progress: Option<(Duration, Progress<'a>)>,
+    /// Continua a busca a partir deste checkpoint
+    pub resume: Option<Checkpoint>,
+    /// Por que a busca parou antes do fim, se parou
+    pub stop: Option<Stop>,
+    /// Estado da busca quando ela parou antes do fim
+    pub checkpoint: Option<Checkpoint>,
+    /// Passos desde a última verificação do relógio
+    ticks: u32,
+    next_progress: Option<Instant>,
+}
+
+impl Default for Control<'_> {
+    fn default() -> Self {
+        Control::new()
+    }
+}
+
+impl<'a> Control<'a> {
+    /// Sem limites nem progresso
+    pub fn new() -> Control<'a> {
+        Control {
+            max_nodes: None,
+            deadline: None,
+            interrupt: None,
+            progress: None,
+            resume: None,
+            stop: None,
+            checkpoint: None,
+            ticks: 0,
+            next_progress: None,
+        }
+    }
+
+    /// Chamado a cada passo da busca, com os nós visitados nesta busca e o número de níveis na
+    /// pilha. Devolve true se a busca deve parar. O relógio só é consultado a cada 1024 passos
+    pub(crate) fn check(&mut self, nodes: u64, depth: usize) -> bool {
+        const TICKS: u32 = 1024;
+        if self.max_nodes.is_some_and(|max| nodes >= max) {
+            self.stop = Some(Stop::Nodes);
+        } else if self.interrupt.is_some_and(|i| i.load(Ordering::Relaxed)) {
+            self.stop = Some(Stop::Interrupt);
+        }
+        self.ticks += 1;
+        if self.stop.is_none() && self.ticks >= TICKS {
+            self.ticks = 0;
+            let now = Instant::now();
+            if self.deadline.is_some_and(|d| now >= d) {
+                self.stop = Some(Stop::Time);
+            }
+            if let Some((every, report)) = &mut self.progress {
+                let next = *self.next_progress.get_or_insert(now + *every);
+                if now >= next {
+                    report(nodes, depth);
+                    self.next_progress = Some(now + *every);
+                }
+            }
+        }
+        self.stop.is_some()
+    }
+}
+
+/// Escreve as linhas do tabuleiro de [table::format] com o cabeçalho "`name` linhas"
+fn format_table(s: &mut String, name: &str, table: &Table) {
+    *s += &format!("{name} {}\n", table.len());
+    *s += &table::format(table);
+}
+
+/// Lista de números separados por espaços
+fn numbers<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values
+        .map(|v| v.to_string())
+        .collect::<Box<[String]>>()
+        .join(" ")
+}
+
+impl Checkpoint {
+    /// Escreve o checkpoint no formato do arquivo
+    pub fn format(&self) -> String {
+        let mut s = String::from("pentaminos checkpoint\n");
+        s += &format!("pieces {}\n", self.pieces);
+        s += &format!("inventory {}\n", numbers(self.inventory.iter()));
+        s += &format!(
+            "options {} {} {}\n",
+            self.strategy.name(),
+            self.any as u8,
+            self.break_symmetry as u8
+        );
+        s += &format!("solutions {} {}\n", self.total, self.distinct);
+        s += &format!("nodes {}\n", self.nodes);
+        s += &format!("left {}\n", numbers(self.left.iter()));
+        s += &format!("stamp {}\n", self.stamp);
+        format_table(&mut s, "board", &self.board);
+        format_table(&mut s, "table", &self.table);
+        s += &format!("mark {}\n", self.mark.len());
+        for line in self.mark.iter() {
+            s += &numbers(line.iter());
+            s.push('\n');
+        }
+        s += &format!("stack {}\n", self.stack.len());
+        for state in self.stack.iter() {
+            let Placement { p_idx, t_idx, pos } = state.placed;
+            s += &format!(
+                "{} {} {} {} {} {p_idx} {t_idx} {} {} {}",
+                state.i,
+                state.t_idx,
+                state.k,
+                state.pos.0,
+                state.pos.1,
+                pos.0,
+                pos.1,
+                state.inserted as u8
+            );
+            for o in state.order.iter() {
+                s += &format!(" {o}");
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    /// Lê o checkpoint do texto do arquivo
+    pub fn parse(text: &str) -> Result<Checkpoint, String> {
+        let mut lines = text.lines();
+        if lines.next() != Some("pentaminos checkpoint") {
+            return Err(invalid("cabeçalho ausente"));
+        }
+        let pieces = field(&mut lines, "pieces")?;
+        let inventory = numbers_in(&field(&mut lines, "inventory")?)?;
+        let options = field(&mut lines, "options")?;
+        let (strategy, any, break_symmetry) = match *options.split(' ').collect::<Box<[_]>>() {
+            [strategy, any @ ("0" | "1"), break_symmetry @ ("0" | "1")] => (
+                Strategy::from_name(strategy).ok_or_else(|| invalid("estratégia inválida"))?,
+                any == "1",
+                break_symmetry == "1",
+            ),
+            _ => return Err(invalid("options deve ter a estratégia, 0 ou 1 e 0 ou 1")),
+        };
+        let [total, distinct] = *numbers_in(&field(&mut lines, "solutions")?)? else {
+            return Err(invalid("solutions deve ter dois números"));
+        };
+        let [nodes] = *numbers_in(&field(&mut lines, "nodes")?)? else {
+            return Err(invalid("nodes deve ter um número"));
+        };
+        let left = numbers_in(&field(&mut lines, "left")?)?;
+        let [stamp] = *numbers_in(&field(&mut lines, "stamp")?)? else {
+            return Err(invalid("stamp deve ter um número"));
+        };
+        let board = table::parse(&section(&mut lines, "board")?.join("\n"))?;
+        let table = table::parse(&section(&mut lines, "table")?.join("\n"))?;
+        let mut mark = Pilha::new();
+        for line in section(&mut lines, "mark")?.iter() {
+            let line: Box<[u32]> = numbers_in(line)?.iter().map(|&m| m as u32).collect();
+            mark.push(line);
+        }
+        let mut stack = Pilha::new();
+        for line in section(&mut lines, "stack")?.iter() {
+            let v = numbers_in(line)?;
+            if v.len() < 10 {
+                return Err(invalid("nível da pilha incompleto"));
+            }
+            let n = |k: usize| v[k] as usize;
+            let c = |k: usize| v[k] as i32;
+            stack.push(State {
+                i: n(0),
+                t_idx: n(1),
+                k: n(2),
+                pos: (c(3), c(4)),
+                placed: Placement {
+                    p_idx: n(5),
+                    t_idx: n(6),
+                    pos: (c(7), c(8)),
+                },
+                inserted: v[9] != 0,
+                order: v[10..].iter().map(|&o| o as usize).collect(),
+            });
+        }
+        Ok(Checkpoint {
+            pieces,
+            board,
+            inventory: inventory.iter().map(|&c| c as usize).collect(),
+            any,
+            strategy,
+            break_symmetry,
+            total,
+            distinct,
+            nodes,
+            left: left.iter().map(|&l| l as usize).collect(),
+            table,
+            mark: mark.to_array(),
+            stamp: stamp as u32,
+            stack: stack.to_array(),
+        })
+    }
+}
+
+/// Erro de leitura do checkpoint
+fn invalid(msg: &str) -> String {
+    format!("Checkpoint inválido: {msg}")
+}
+
+/// Valor da próxima linha, que deve ser "`name` valor"
+fn field(lines: &mut std::str::Lines, name: &str) -> Result<String, String> {
+    match lines.next().and_then(|l| l.split_once(' ')) {
+        Some((n, value)) if n == name => Ok(value.to_string()),
+        _ => Err(invalid(&format!("faltou {name}"))),
+    }
+}
+
+/// Números (não negativos) separados por espaços
+fn numbers_in(s: &str) -> Result<Box<[u64]>, String> {
+    s.split_whitespace()
+        .map(|n| {
+            n.parse::<u64>()
+                .map_err(|_| invalid(&format!("número inválido {n}")))
+        })
+        .collect()
+}
+
+/// Linhas da seção com cabeçalho "`name` número de linhas"
+fn section<'a>(lines: &mut std::str::Lines<'a>, name: &str) -> Result<Box<[&'a str]>, String> {
+    let count = field(lines, name)?
+        .parse::<usize>()
+        .map_err(|_| invalid(&format!("tamanho inválido em {name}")))?;
+    let mut body = Pilha::new();
+    for _ in 0..count {
+        body.push(
+            lines
+                .next()
+                .ok_or_else(|| invalid(&format!("{name} incompleto")))?,
+        );
+    }
+    Ok(body.to_array())
+}
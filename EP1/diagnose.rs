@@ -0,0 +1,369 @@
+//! Explicações para tabuleiros sem solução: antes de buscar, procura provas conhecidas de que o
+//! tabuleiro não pode ser coberto pelas peças e, se não encontra nenhuma, faz a busca completa.
+//!
+//! As provas são:
+//! - o número de quadrados vazios não é a área das peças (ver [Inventory::fits]);
+//! - uma peça que deve ser usada não cabe em nenhuma posição;
+//! - uma região (componente conexa de quadrados vazios) não pode ser coberta pelas peças: o seu
+//!   tamanho não é múltiplo do mdc dos tamanhos das peças, é menor que a menor peça ou, se só
+//!   comporta uma peça, nenhuma tem a sua forma (a mesma análise da poda do [crate::backtrack]);
+//! - a coloração de xadrez: cada peça cobre uma diferença fixa entre quadrados pretos e brancos,
+//!   e nenhuma combinação dessas diferenças dá a do tabuleiro.
+
+use super::array;
+use super::backtrack::sizes_left;
+use super::grid::Grid;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::{Pieces, Poliomino};
+use super::solver::{self, Options, SolveError};
+use super::table::{self, idx, Table, Topology};
+
+/// Prova de que o tabuleiro não tem solução. As posições são (linha, coluna) do tabuleiro dado
+#[derive(Clone, Debug, PartialEq)]
+pub enum Proof {
+    /// O número de quadrados vazios não corresponde à área das peças (se nem todas as peças
+    /// precisam ser usadas, `exact` é falso e há quadrados demais)
+    CellCount {
+        cells: usize,
+        area: usize,
+        exact: bool,
+    },
+    /// A peça deve ser usada, mas não cabe em nenhuma posição do tabuleiro
+    NoPlacement { piece: char },
+    /// A região de `size` quadrados, cujo primeiro quadrado é `cell`, não tem tamanho múltiplo
+    /// do mdc dos tamanhos das peças
+    RegionSize {
+        cell: (i32, i32),
+        size: usize,
+        gcd: usize,
+    },
+    /// A região é menor que a menor peça
+    SmallRegion {
+        cell: (i32, i32),
+        size: usize,
+        min: usize,
+    },
+    /// A região só comporta uma peça, mas nenhuma peça tem a sua forma
+    RegionShape { cell: (i32, i32), size: usize },
+    /// Pintando o tabuleiro como um tabuleiro de xadrez, cada peça cobre uma diferença fixa
+    /// entre quadrados pretos e brancos (`imbalances`, das peças em que ela não é zero), e
+    /// nenhuma escolha de sinais dessas diferenças soma a diferença do tabuleiro
+    Coloring {
+        black: usize,
+        white: usize,
+        imbalances: Box<[(char, usize)]>,
+    },
+}
+
+/// Tamanho da região por extenso (ex.: "1 quadrado vazio", "4 quadrados vazios")
+fn empty(size: usize) -> String {
+    if size == 1 {
+        "1 quadrado vazio".to_string()
+    } else {
+        format!("{size} quadrados vazios")
+    }
+}
+
+/// Valor inicial dos elementos da [Pilha]
+impl Default for Proof {
+    fn default() -> Proof {
+        Proof::CellCount {
+            cells: 0,
+            area: 0,
+            exact: true,
+        }
+    }
+}
+
+impl std::fmt::Display for Proof {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Proof::CellCount { cells, area, exact } if *exact => write!(
+                f,
+                "o tabuleiro tem {cells} quadrados vazios, mas as peças têm {area} quadrados"
+            ),
+            Proof::CellCount { cells, area, .. } => write!(
+                f,
+                "o tabuleiro tem {cells} quadrados vazios, mais que os {area} quadrados das peças"
+            ),
+            Proof::NoPlacement { piece } => {
+                write!(f, "a peça {piece} não cabe em nenhuma posição do tabuleiro")
+            }
+            Proof::RegionSize { cell, size, gcd } => write!(
+                f,
+                "a região de {} em {cell:?} não tem tamanho múltiplo de {gcd} (o mdc dos \
+                 tamanhos das peças)",
+                empty(*size)
+            ),
+            Proof::SmallRegion { cell, size, min } => write!(
+                f,
+                "a região de {} em {cell:?} é menor que a menor peça ({min} quadrados)",
+                empty(*size)
+            ),
+            Proof::RegionShape { cell, size } => write!(
+                f,
+                "a região de {} em {cell:?} só comporta uma peça, mas nenhuma peça tem a \
+                 sua forma",
+                empty(*size)
+            ),
+            Proof::Coloring {
+                black,
+                white,
+                imbalances,
+            } => {
+                write!(
+                    f,
+                    "pintado como um tabuleiro de xadrez, o tabuleiro tem {black} quadrados \
+                     pretos e {white} brancos (diferença {}), mas ",
+                    black.abs_diff(*white)
+                )?;
+                if imbalances.is_empty() {
+                    return write!(f, "cada peça cobre tantos pretos quanto brancos");
+                }
+                let list: Box<[String]> = imbalances
+                    .iter()
+                    .map(|(ident, d)| format!("{ident}: {d}"))
+                    .collect();
+                write!(
+                    f,
+                    "as peças cobrem diferenças fixas ({}, as outras 0) e nenhuma combinação \
+                     delas dá essa diferença",
+                    list.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Resultado do diagnóstico
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagnosis {
+    /// O tabuleiro não tem solução, pelas provas dadas
+    Impossible(Box<[Proof]>),
+    /// Nenhuma prova se aplica e foi preciso buscar: a busca visitou `nodes` nós e encontrou
+    /// uma solução se `solvable`
+    Searched { nodes: u64, solvable: bool },
+}
+
+/// Procura provas de que o tabuleiro não tem solução com as peças e opções dadas; se não houver
+/// nenhuma, busca a primeira solução
+pub fn diagnose(table: &Table, opts: &Options) -> Result<Diagnosis, SolveError> {
+    let proofs = proofs(table, opts)?;
+    if !proofs.is_empty() {
+        return Ok(Diagnosis::Impossible(proofs));
+    }
+    let (results, stats) = solver::search(table, opts, &|_, found: &mut bool| {
+        *found = true;
+        false
+    })?;
+    Ok(Diagnosis::Searched {
+        nodes: stats.nodes,
+        solvable: results.iter().any(|&found| found),
+    })
+}
+
+/// Devolve as provas de que o tabuleiro não tem solução (vazio se nenhuma se aplica)
+pub fn proofs(table: &Table, opts: &Options) -> Result<Box<[Proof]>, SolveError> {
+    solver::validate(table)?;
+    let inventory = match &opts.inventory {
+        Some(inventory) => inventory.clone(),
+        None => Inventory::all(&opts.pieces, true),
+    };
+    let inventory = solver::preplaced(table, &opts.pieces, inventory)?;
+    solver::check_wrap(table, &opts.pieces, &inventory, opts.topology)?;
+    let pieces = &opts.pieces;
+    let mut proofs = Pilha::new();
+    let cells = table::empty_spaces(table);
+    if !inventory.fits(pieces, cells) {
+        proofs.push(Proof::CellCount {
+            cells,
+            area: inventory.area(pieces),
+            exact: inventory.exact,
+        });
+    }
+    if inventory.exact {
+        for (p, _) in pieces
+            .iter()
+            .zip(inventory.counts.iter())
+            .filter(|(_, &c)| c > 0)
+        {
+            if !placeable(table, p, opts.topology) {
+                proofs.push(Proof::NoPlacement { piece: p[0].ident });
+            }
+        }
+    }
+    regions(table, pieces, &inventory.counts, opts.topology, &mut proofs);
+    if let Some(proof) = coloring(table, pieces, &inventory, opts.topology) {
+        proofs.push(proof);
+    }
+    Ok(proofs.to_array())
+}
+
+/// Quadrados (0, 0) e pt do poliominó
+fn squares(p: &Poliomino) -> impl Iterator<Item = (i32, i32)> + '_ {
+    std::iter::once((0, 0)).chain(p.pt.iter().copied())
+}
+
+/// Checa se alguma transformação da peça cabe em alguma posição do tabuleiro
+fn placeable(table: &Table, transformations: &[Poliomino], topology: Topology) -> bool {
+    let (lin, col) = table::dim(table);
+    (0..lin).any(|x| {
+        (0..col).any(|y| {
+            transformations
+                .iter()
+                .any(|t| t.fits_in(x, y, table, topology))
+        })
+    })
+}
+
+/// Acrescenta em `proofs` as regiões de quadrados vazios que não podem ser cobertas pelas peças
+/// com as cópias `left`
+fn regions(
+    table: &Table,
+    pieces: &Pieces,
+    left: &[usize],
+    topology: Topology,
+    proofs: &mut Pilha<Proof>,
+) {
+    let (g, min) = sizes_left(pieces, left);
+    if g == 0 {
+        // Não há peças: o número de quadrados já não bate
+        return;
+    }
+    let (lin, col) = table::dim(table);
+    let mut seen = array::new::<Box<[bool]>>(idx(lin));
+    for line in seen.iter_mut() {
+        *line = array::new(idx(col));
+    }
+    for x in 0..lin {
+        for y in 0..col {
+            if table[idx(x)][idx(y)] != ' ' || seen[idx(x)][idx(y)] {
+                continue;
+            }
+            // Busca em profundidade pela região, cujo primeiro quadrado (linha a linha) é (x, y)
+            let mut region: Pilha<(i32, i32)> = Pilha::new();
+            let mut stack = Pilha::new();
+            stack.push((x, y));
+            seen[idx(x)][idx(y)] = true;
+            while !stack.empty() {
+                let (cx, cy) = *stack.back();
+                stack.pop();
+                region.push((cx, cy));
+                for &(dx, dy) in topology.grid.neighbours((cx, cy)) {
+                    let Some((px, py)) = topology.wrap((cx + dx, cy + dy), (lin, col)) else {
+                        continue;
+                    };
+                    if table[idx(px)][idx(py)] == ' ' && !seen[idx(px)][idx(py)] {
+                        seen[idx(px)][idx(py)] = true;
+                        stack.push((px, py));
+                    }
+                }
+            }
+            let size = region.size();
+            let cell = (x, y);
+            if !size.is_multiple_of(g) {
+                proofs.push(Proof::RegionSize { cell, size, gcd: g });
+            } else if size < min {
+                proofs.push(Proof::SmallRegion { cell, size, min });
+            } else if size / 2 < min && !shaped(table, pieces, left, topology, cell, size) {
+                proofs.push(Proof::RegionShape { cell, size });
+            }
+        }
+    }
+}
+
+/// Checa se alguma peça restante de `size` quadrados cobre exatamente a região que contém
+/// `cell`: como a peça é conexa e tem o tamanho da região, basta que caiba cobrindo `cell`
+fn shaped(
+    table: &Table,
+    pieces: &Pieces,
+    left: &[usize],
+    topology: Topology,
+    (x, y): (i32, i32),
+    size: usize,
+) -> bool {
+    pieces
+        .iter()
+        .zip(left.iter())
+        .filter(|(p, &copies)| copies > 0 && p[0].size() == size)
+        .any(|(p, _)| {
+            p.iter().any(|t| {
+                squares(t).any(|(dx, dy)| {
+                    match topology.wrap((x - dx, y - dy), table::dim(table)) {
+                        Some((px, py)) => t.fits_in(px, py, table, topology),
+                        None => false,
+                    }
+                })
+            })
+        })
+}
+
+/// Coloração de xadrez: devolve a prova se nenhuma escolha de sinais das diferenças entre
+/// quadrados pretos e brancos das peças dá a diferença do tabuleiro. Só se aplica na grade
+/// quadrada, com todas as peças obrigatórias e com um número par de linhas (colunas) se elas
+/// dão a volta, para que as cores continuem alternando
+fn coloring(
+    table: &Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    topology: Topology,
+) -> Option<Proof> {
+    let (lin, col) = table::dim(table);
+    if topology.grid != Grid::Square
+        || !inventory.exact
+        || (topology.wrap_lines && lin % 2 != 0)
+        || (topology.wrap_columns && col % 2 != 0)
+    {
+        return None;
+    }
+    let (mut black, mut white) = (0usize, 0usize);
+    for x in 0..lin {
+        for y in 0..col {
+            if table[idx(x)][idx(y)] == ' ' {
+                if (x + y) % 2 == 0 {
+                    black += 1;
+                } else {
+                    white += 1;
+                }
+            }
+        }
+    }
+    // Diferença de cada peça, que não muda com rotações e reflexões (apenas o sinal muda com a
+    // posição)
+    let mut imbalances: Pilha<(char, usize)> = Pilha::new();
+    let mut copies: Pilha<usize> = Pilha::new();
+    for (p, &count) in pieces.iter().zip(inventory.counts.iter()) {
+        let even = squares(&p[0])
+            .filter(|(dx, dy)| (dx + dy).rem_euclid(2) == 0)
+            .count();
+        let d = even.abs_diff(p[0].size() - even);
+        if count > 0 && d > 0 {
+            imbalances.push((p[0].ident, d));
+            for _ in 0..count {
+                copies.push(d);
+            }
+        }
+    }
+    // reachable[s]: a soma s - total pode ser obtida com as cópias já vistas
+    let total: usize = copies.iter().sum();
+    let mut reachable = array::new::<bool>(2 * total + 1);
+    reachable[total] = true;
+    for &d in copies.iter() {
+        let mut next = array::new::<bool>(2 * total + 1);
+        for (s, _) in reachable.iter().enumerate().filter(|(_, &r)| r) {
+            next[s - d] = true;
+            next[s + d] = true;
+        }
+        reachable = next;
+    }
+    let diff = black.abs_diff(white);
+    if diff <= total && reachable[total + diff] {
+        return None;
+    }
+    Some(Proof::Coloring {
+        black,
+        white,
+        imbalances: imbalances.to_array(),
+    })
+}
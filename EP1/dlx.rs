@@ -0,0 +1,330 @@
+//! Cobertura exata com Dancing Links (Algoritmo X de Knuth)
+//!
+//! O tabuleiro vira uma matriz de 0s e 1s com uma coluna para cada peça e uma para cada
+//! quadrado vazio, e uma linha para cada maneira de colocar uma peça no tabuleiro. Uma
+//! solução é um conjunto de linhas que tem exatamente um 1 em cada coluna de quadrado e, em
+//! cada coluna de peça, no máximo tantos 1s quanto o número de cópias da peça.
+
+use super::array;
+use super::constraints::Constraints;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::{Pieces, Placement};
+use super::table::{dim, idx, Table, Topology};
+
+/// Matriz esparsa da cobertura exata, em que cada 1 é um nó de listas duplamente ligadas
+/// circulares na horizontal (linha) e na vertical (coluna).
+/// O nó 0 é a raiz, os nós 1..=colunas são os cabeçalhos das colunas e os restantes são os 1s.
+pub struct Dlx {
+    left: Box<[usize]>,
+    right: Box<[usize]>,
+    up: Box<[usize]>,
+    down: Box<[usize]>,
+    /// Cabeçalho da coluna de cada nó
+    col: Box<[usize]>,
+    /// Linha de cada nó
+    row: Box<[usize]>,
+    /// Número de nós em cada coluna
+    size: Box<[usize]>,
+    /// Indica se a coluna é secundária, isto é, não precisa ser coberta
+    secondary: Box<[bool]>,
+    /// Para colunas secundárias, quantas linhas com 1 na coluna ainda podem ser escolhidas
+    capacity: Box<[usize]>,
+}
+
+impl Dlx {
+    /// Monta a matriz com `columns` colunas, em que rows\[i\] lista as colunas com 1 na linha i
+    pub fn new(columns: usize, rows: &[Box<[usize]>]) -> Dlx {
+        let nodes = 1 + columns + rows.iter().map(|r| r.len()).sum::<usize>();
+        let mut dlx = Dlx {
+            left: array::new(nodes),
+            right: array::new(nodes),
+            up: array::new(nodes),
+            down: array::new(nodes),
+            col: array::new(nodes),
+            row: array::new(nodes),
+            size: array::new(columns + 1),
+            secondary: array::new(columns + 1),
+            capacity: array::new(columns + 1),
+        };
+        for c in 0..=columns {
+            dlx.left[c] = if c == 0 { columns } else { c - 1 };
+            dlx.right[c] = if c == columns { 0 } else { c + 1 };
+            dlx.up[c] = c;
+            dlx.down[c] = c;
+            dlx.col[c] = c;
+        }
+        let mut node = columns + 1;
+        for (r, cols) in rows.iter().enumerate() {
+            let first = node;
+            for (k, &c) in cols.iter().enumerate() {
+                let c = c + 1;
+                dlx.col[node] = c;
+                dlx.row[node] = r;
+                dlx.up[node] = dlx.up[c];
+                dlx.down[node] = c;
+                dlx.down[dlx.up[c]] = node;
+                dlx.up[c] = node;
+                dlx.size[c] += 1;
+                dlx.left[node] = if k == 0 {
+                    first + cols.len() - 1
+                } else {
+                    node - 1
+                };
+                dlx.right[node] = if k + 1 == cols.len() { first } else { node + 1 };
+                node += 1;
+            }
+        }
+        dlx
+    }
+
+    /// Torna a coluna c secundária: ela não precisa ser coberta, mas no máximo `capacity` linhas
+    /// com 1 nela podem ser escolhidas
+    pub fn set_capacity(&mut self, c: usize, capacity: usize) {
+        let c = c + 1;
+        assert!(!self.secondary[c], "Capacidade da coluna {} já definida", c);
+        // Tira a coluna da lista de colunas a serem cobertas
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        self.left[c] = c;
+        self.right[c] = c;
+        self.secondary[c] = true;
+        self.capacity[c] = capacity;
+        if capacity == 0 {
+            self.cover(c);
+        }
+    }
+
+    /// Remove a coluna c e todas as linhas que têm 1 nela
+    fn cover(&mut self, c: usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.col[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    /// Desfaz cover(c)
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.col[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+
+    /// Escolhe a linha do nó r, cobrindo as outras colunas em que ela tem 1.
+    /// Uma coluna secundária só é coberta quando a sua capacidade acaba (a linha r já foi
+    /// removida das colunas ao cobrir a coluna de r)
+    fn select(&mut self, r: usize) {
+        let mut j = self.right[r];
+        while j != r {
+            let c = self.col[j];
+            if !self.secondary[c] {
+                self.cover(c);
+            } else {
+                self.capacity[c] -= 1;
+                if self.capacity[c] == 0 {
+                    self.cover(c);
+                }
+            }
+            j = self.right[j];
+        }
+    }
+
+    /// Desfaz select(r)
+    fn unselect(&mut self, r: usize) {
+        let mut j = self.left[r];
+        while j != r {
+            let c = self.col[j];
+            if !self.secondary[c] {
+                self.uncover(c);
+            } else {
+                if self.capacity[c] == 0 {
+                    self.uncover(c);
+                }
+                self.capacity[c] += 1;
+            }
+            j = self.left[j];
+        }
+    }
+
+    /// Devolve a coluna com menos 1s (0 se todas as colunas foram cobertas)
+    fn min_column(&self) -> usize {
+        let mut best = 0;
+        let mut c = self.right[0];
+        while c != 0 {
+            if best == 0 || self.size[c] < self.size[best] {
+                best = c;
+            }
+            c = self.right[c];
+        }
+        best
+    }
+
+    /// Algoritmo X, sempre escolhendo a coluna com menos opções.
+    /// Para cada solução chama `found` com os índices das linhas escolhidas;
+    /// a busca continua enquanto `found` devolver true.
+    /// Devolve o número de nós visitados (linhas escolhidas)
+    pub fn solve(&mut self, mut found: impl FnMut(&[usize]) -> bool) -> u64 {
+        /// Nível da busca: coluna coberta e nó da linha escolhida para ela
+        #[derive(Clone, Copy, Default)]
+        struct Level {
+            col: usize,
+            node: usize,
+        }
+        let mut state: Pilha<Level> = Pilha::new();
+        let mut nodes = 0;
+        'search: loop {
+            // Desce na árvore de busca enquanto possível
+            let c = self.min_column();
+            let descended = if c == 0 {
+                let mut rows = array::new::<usize>(state.size());
+                for (i, level) in state.iter().enumerate() {
+                    rows[i] = self.row[level.node];
+                }
+                if !found(&rows) {
+                    break;
+                }
+                false
+            } else if self.size[c] == 0 {
+                false
+            } else {
+                self.cover(c);
+                let node = self.down[c];
+                self.select(node);
+                nodes += 1;
+                state.push(Level { col: c, node });
+                true
+            };
+            if descended {
+                continue;
+            }
+            // Volta até encontrar um nível com outra linha a ser tentada
+            while !state.empty() {
+                let level = state.back();
+                let (col, node) = (level.col, level.node);
+                self.unselect(node);
+                let next = self.down[node];
+                if next != col {
+                    state.back().node = next;
+                    self.select(next);
+                    nodes += 1;
+                    continue 'search;
+                }
+                self.uncover(col);
+                state.pop();
+            }
+            break;
+        }
+        // Restaura a matriz caso a busca tenha sido interrompida
+        while !state.empty() {
+            let Level { col, node } = *state.back();
+            self.unselect(node);
+            self.uncover(col);
+            state.pop();
+        }
+        nodes
+    }
+}
+
+/// Resolve o tabuleiro como um problema de cobertura exata, em que só há linhas para as
+/// colocações permitidas pelas restrições (dando a volta nas bordas ligadas pela topologia).
+/// Cada vez que o tabuleiro é totalmente preenchido, chama `found` com o tabuleiro resolvido e
+/// as colocações das peças; a busca continua enquanto `found` devolver true.
+/// Devolve o número de nós visitados
+pub fn search(
+    table: &mut Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    constraints: &Constraints,
+    topology: Topology,
+    mut found: impl FnMut(&Table, &[Placement]) -> bool,
+) -> u64 {
+    let (lin, col) = dim(table);
+    // Numerar os quadrados vazios: a coluna do quadrado (x, y) é n + cell[x][y]
+    let n = pieces.len();
+    let mut cell = array::new::<Box<[usize]>>(idx(lin));
+    let mut cells = 0;
+    for x in 0..lin {
+        cell[idx(x)] = array::new::<usize>(idx(col));
+        for y in 0..col {
+            if table[idx(x)][idx(y)] == ' ' {
+                cell[idx(x)][idx(y)] = cells;
+                cells += 1;
+            }
+        }
+    }
+
+    let mut placements: Pilha<Placement> = Pilha::new();
+    let mut rows: Pilha<Box<[usize]>> = Pilha::new();
+    for (p_idx, transformations) in pieces.iter().enumerate() {
+        for (t_idx, p) in transformations.iter().enumerate() {
+            for x in 0..lin {
+                for y in 0..col {
+                    let placement = Placement {
+                        p_idx,
+                        t_idx,
+                        pos: (x, y),
+                    };
+                    if !p.fits_in(x, y, table, topology)
+                        || !constraints.allows(&placement, pieces, topology)
+                    {
+                        continue;
+                    }
+                    let mut row = array::new::<usize>(p.size() + 1);
+                    row[0] = p_idx;
+                    row[1] = n + cell[idx(x)][idx(y)];
+                    for (k, &(dx, dy)) in p.pt.iter().enumerate() {
+                        let (px, py) = topology.wrap((x + dx, y + dy), (lin, col)).unwrap();
+                        row[k + 2] = n + cell[idx(px)][idx(py)];
+                    }
+                    rows.push(row);
+                    placements.push(placement);
+                }
+            }
+        }
+    }
+
+    let mut dlx = Dlx::new(n + cells, &rows.to_array());
+    for (p_idx, &copies) in inventory.counts.iter().enumerate() {
+        // Se todas as peças devem ser usadas, uma peça com uma cópia é uma coluna primária
+        if !(inventory.exact && copies == 1) {
+            dlx.set_capacity(p_idx, copies);
+        }
+    }
+    let placements = placements.to_array();
+    dlx.solve(|rows| {
+        let mut chosen = array::new::<Placement>(rows.len());
+        for (i, &r) in rows.iter().enumerate() {
+            let Placement { p_idx, t_idx, pos } = placements[r];
+            assert!(
+                pieces[p_idx][t_idx].try_put(pos.0, pos.1, table, topology),
+                "Linha da cobertura exata inválida"
+            );
+            chosen[i] = placements[r];
+        }
+        let keep_going = found(table, &chosen);
+        for &r in rows {
+            let Placement { p_idx, t_idx, pos } = placements[r];
+            pieces[p_idx][t_idx].remove(pos.0, pos.1, table, topology);
+        }
+        keep_going
+    })
+}
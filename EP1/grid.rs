@@ -0,0 +1,165 @@
+//! Geometria da grade do tabuleiro: quais quadrados são vizinhos e como as peças giram e
+//! refletem.
+//!
+//! Todas as grades usam as mesmas coordenadas (linha, coluna) do tabuleiro:
+//! - quadrada: os vizinhos de (x, y) são (x ± 1, y) e (x, y ± 1);
+//! - hexagonal: coordenadas axiais, em que cada linha é deslocada meio hexágono em relação à
+//!   anterior; além dos 4 vizinhos da quadrada, (x - 1, y + 1) e (x + 1, y - 1) também são;
+//! - triangular: cada linha alterna triângulos apontando para cima (x + y par) e para baixo
+//!   (x + y ímpar); os vizinhos são (x, y ± 1) e (x + 1, y) ou (x - 1, y), respectivamente.
+
+/// Operações de uma geometria, usadas pelas peças, pela busca e pelas simetrias
+pub trait Geometry: Sync {
+    /// Deslocamentos dos vizinhos do quadrado (x, y)
+    fn neighbours(&self, cell: (i32, i32)) -> &'static [(i32, i32)];
+    /// Número de rotações distintas de uma peça (incluindo a identidade)
+    fn rotations(&self) -> usize;
+    /// Gira o quadrado em torno da origem (a menor rotação que leva a grade nela mesma)
+    fn rotate(&self, cell: (i32, i32)) -> (i32, i32);
+    /// Reflete o quadrado
+    fn reflect(&self, cell: (i32, i32)) -> (i32, i32);
+    /// Tipo do quadrado: uma peça só pode ser transladada entre quadrados do mesmo tipo (os
+    /// triângulos para baixo na grade triangular)
+    fn parity(&self, _cell: (i32, i32)) -> bool {
+        false
+    }
+}
+
+/// Grade quadrada (poliominós)
+pub struct Square;
+
+/// Grade hexagonal (polihexes)
+pub struct Hex;
+
+/// Grade triangular (poliamantes)
+pub struct Triangle;
+
+const SQUARE: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+impl Geometry for Square {
+    fn neighbours(&self, _cell: (i32, i32)) -> &'static [(i32, i32)] {
+        &SQUARE
+    }
+
+    fn rotations(&self) -> usize {
+        4
+    }
+
+    fn rotate(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (y, -x)
+    }
+
+    fn reflect(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (x, -y)
+    }
+}
+
+impl Geometry for Hex {
+    fn neighbours(&self, _cell: (i32, i32)) -> &'static [(i32, i32)] {
+        &[(1, 0), (0, 1), (-1, 0), (0, -1), (-1, 1), (1, -1)]
+    }
+
+    fn rotations(&self) -> usize {
+        6
+    }
+
+    // Nas coordenadas cúbicas (q, r, s) = (y, x, -x - y), girar 60° leva (q, r, s) em
+    // (-r, -s, -q)
+    fn rotate(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (x + y, -x)
+    }
+
+    fn reflect(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (y, x)
+    }
+}
+
+impl Triangle {
+    /// Centro do triângulo nas coordenadas da rede de vértices (a base (1, 0) e (1/2, √3/2)),
+    /// multiplicado por 3 para ser inteiro: o triângulo para cima com vértices (i, j),
+    /// (i + 1, j) e (i, j + 1) tem centro (3i + 1, 3j + 1) e o para baixo, (3i + 2, 3j + 2).
+    /// A linha x do tabuleiro é a faixa j = -x da rede, já que as linhas crescem para baixo
+    fn center((x, y): (i32, i32)) -> (i32, i32) {
+        let d = (x + y).rem_euclid(2);
+        let i = (x + y - d).div_euclid(2);
+        (3 * i + 1 + d, 1 + d - 3 * x)
+    }
+
+    /// Devolve o triângulo com o centro dado (ver [Triangle::center])
+    fn cell((a, b): (i32, i32)) -> (i32, i32) {
+        let d = (a - 1).rem_euclid(3);
+        debug_assert!(d < 2 && (b - 1).rem_euclid(3) == d, "Centro inválido");
+        let (i, j) = ((a - 1 - d) / 3, (b - 1 - d) / 3);
+        (-j, 2 * i + d + j)
+    }
+}
+
+impl Geometry for Triangle {
+    fn neighbours(&self, cell: (i32, i32)) -> &'static [(i32, i32)] {
+        if self.parity(cell) {
+            &[(0, 1), (0, -1), (-1, 0)]
+        } else {
+            &[(0, 1), (0, -1), (1, 0)]
+        }
+    }
+
+    fn rotations(&self) -> usize {
+        6
+    }
+
+    // Girar 60° leva o vetor (a, b) da rede em (-b, a + b)
+    fn rotate(&self, cell: (i32, i32)) -> (i32, i32) {
+        let (a, b) = Triangle::center(cell);
+        Triangle::cell((-b, a + b))
+    }
+
+    fn reflect(&self, cell: (i32, i32)) -> (i32, i32) {
+        let (a, b) = Triangle::center(cell);
+        Triangle::cell((b, a))
+    }
+
+    fn parity(&self, (x, y): (i32, i32)) -> bool {
+        (x + y).rem_euclid(2) == 1
+    }
+}
+
+/// Grade usada pelo tabuleiro e pelas peças
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Grid {
+    #[default]
+    Square,
+    Hex,
+    Triangle,
+}
+
+impl Grid {
+    /// Operações da grade
+    pub fn geometry(self) -> &'static dyn Geometry {
+        match self {
+            Grid::Square => &Square,
+            Grid::Hex => &Hex,
+            Grid::Triangle => &Triangle,
+        }
+    }
+
+    /// O mesmo que [Geometry::neighbours], sem a chamada dinâmica (usado na busca)
+    pub fn neighbours(self, cell: (i32, i32)) -> &'static [(i32, i32)] {
+        match self {
+            Grid::Square => Square.neighbours(cell),
+            Grid::Hex => Hex.neighbours(cell),
+            Grid::Triangle => Triangle.neighbours(cell),
+        }
+    }
+
+    /// O mesmo que [Geometry::parity], sem a chamada dinâmica (usado na busca)
+    pub fn parity(self, cell: (i32, i32)) -> bool {
+        self == Grid::Triangle && Triangle.parity(cell)
+    }
+
+    /// Checa se os quadrados a e b são vizinhos
+    pub fn adjacent(self, a: (i32, i32), b: (i32, i32)) -> bool {
+        self.neighbours(a)
+            .iter()
+            .any(|&(dx, dy)| (a.0 + dx, a.1 + dy) == b)
+    }
+}
@@ -0,0 +1,68 @@
+//! Inventário: quantas vezes cada peça pode ser usada
+
+use super::array;
+use super::poliomino::Pieces;
+
+/// counts\[i\]: número de cópias da i-ésima peça.
+/// Se `exact`, todas as cópias devem ser usadas; senão são apenas um limite e o tabuleiro é
+/// preenchido com qualquer subconjunto delas
+#[derive(Clone)]
+pub struct Inventory {
+    pub counts: Box<[usize]>,
+    pub exact: bool,
+}
+
+impl Inventory {
+    /// Uma cópia de cada peça
+    pub fn all(pieces: &Pieces, exact: bool) -> Inventory {
+        let mut counts = array::new::<usize>(pieces.len());
+        for c in counts.iter_mut() {
+            *c = 1;
+        }
+        Inventory { counts, exact }
+    }
+
+    /// Lê o inventário de uma lista separada por vírgulas de identificadores de peças, cada um
+    /// seguido opcionalmente pelo número de cópias (ex.: "F2,L2,P2,T2" ou "F,I,L").
+    /// Peças que não aparecem na lista não são usadas
+    pub fn parse(s: &str, pieces: &Pieces, exact: bool) -> Result<Inventory, String> {
+        let mut counts = array::new::<usize>(pieces.len());
+        for item in s.split(',').map(str::trim) {
+            let mut chars = item.chars();
+            let ident = chars
+                .next()
+                .ok_or_else(|| format!("Item vazio no inventário \"{s}\""))?;
+            let count = match chars.as_str() {
+                "" => 1,
+                n => n
+                    .parse::<usize>()
+                    .map_err(|_| format!("Número de cópias inválido em \"{item}\""))?,
+            };
+            let p_idx = pieces
+                .iter()
+                .position(|p| p[0].ident == ident)
+                .ok_or_else(|| format!("Peça {ident} não existe"))?;
+            counts[p_idx] += count;
+        }
+        Ok(Inventory { counts, exact })
+    }
+
+    /// Número total de quadrados das peças do inventário
+    pub fn area(&self, pieces: &Pieces) -> usize {
+        pieces
+            .iter()
+            .zip(self.counts.iter())
+            .map(|(p, &c)| p[0].size() * c)
+            .sum()
+    }
+
+    /// Checa se um tabuleiro com `spaces` quadrados vazios pode ser preenchido com o inventário,
+    /// olhando apenas para o número de quadrados
+    pub fn fits(&self, pieces: &Pieces, spaces: usize) -> bool {
+        if self.exact {
+            spaces == self.area(pieces)
+        } else {
+            spaces <= self.area(pieces)
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Saída das soluções em JSON, para ser lida por outros programas sem interpretar o tabuleiro
+//! impresso.
+//!
+//! Cada solução é um objeto com as dimensões do tabuleiro, as linhas do tabuleiro preenchido e
+//! as peças colocadas pela busca. Ex.:
+//! ```text
+//! {"lines":1,"columns":5,"board":["IIIII"],"pieces":[{"piece":"I","orientation":0,
+//! "anchor":[0,0],"cells":[[0,0],[0,1],[0,2],[0,3],[0,4]]}]}
+//! ```
+
+use super::array;
+use super::poliomino::{Pieces, Placement};
+use super::solver::Solution;
+use super::table::{self, Table, Topology};
+
+/// Objeto JSON sendo escrito, campo a campo
+pub struct Object {
+    text: String,
+}
+
+impl Object {
+    pub fn new() -> Object {
+        Object {
+            text: String::from("{"),
+        }
+    }
+
+    /// Acrescenta o campo `key` com o valor `value`, que já deve estar em JSON
+    pub fn field(mut self, key: &str, value: &str) -> Object {
+        if self.text.len() > 1 {
+            self.text.push(',');
+        }
+        self.text += &string(key);
+        self.text.push(':');
+        self.text += value;
+        self
+    }
+
+    /// Devolve o texto do objeto
+    pub fn finish(mut self) -> String {
+        self.text.push('}');
+        self.text
+    }
+}
+
+impl Default for Object {
+    fn default() -> Object {
+        Object::new()
+    }
+}
+
+/// Texto como string JSON, com as aspas e os caracteres especiais escapados
+pub fn string(s: &str) -> String {
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            c if (c as u32) < 0x20 => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Lista JSON com os valores dados, que já devem estar em JSON
+pub fn array(values: impl Iterator<Item = String>) -> String {
+    let mut result = String::from("[");
+    for (i, v) in values.enumerate() {
+        if i > 0 {
+            result.push(',');
+        }
+        result += &v;
+    }
+    result.push(']');
+    result
+}
+
+/// Quadrado (linha, coluna) como a lista \[linha, coluna\]
+pub fn cell((x, y): (i32, i32)) -> String {
+    format!("[{x},{y}]")
+}
+
+/// Linhas do tabuleiro como lista de strings, no formato de [table::parse] (com '.' nos
+/// quadrados vazios e '#' nos que não podem ser ocupados)
+pub fn board(table: &Table) -> String {
+    array(table.iter().map(|line| {
+        let line: String = line
+            .iter()
+            .map(|&c| match c {
+                ' ' => '.',
+                '~' => '#',
+                c => c,
+            })
+            .collect();
+        string(&line)
+    }))
+}
+
+/// Colocação como objeto: a letra da peça, o índice da transformação (em pieces\[p_idx\], na
+/// ordem do [crate::poliomino::generator]), o quadrado (0, 0) da transformação e os quadrados
+/// cobertos (em ordem, já dando a volta nas bordas ligadas do tabuleiro)
+pub fn placement(p: &Placement, pieces: &Pieces, table: &Table, topology: Topology) -> String {
+    let t = &pieces[p.p_idx][p.t_idx];
+    let (x, y) = p.pos;
+    let dim = table::dim(table);
+    let mut cells = array::new::<(i32, i32)>(t.size());
+    for (c, (dx, dy)) in cells
+        .iter_mut()
+        .zip(std::iter::once((0, 0)).chain(t.pt.iter().copied()))
+    {
+        *c = topology.wrap((x + dx, y + dy), dim).unwrap();
+    }
+    cells.sort();
+    Object::new()
+        .field("piece", &string(&t.ident.to_string()))
+        .field("orientation", &p.t_idx.to_string())
+        .field("anchor", &cell(p.pos))
+        .field("cells", &array(cells.iter().map(|&c| cell(c))))
+        .finish()
+}
+
+/// Campos da solução: dimensões do tabuleiro, linhas do tabuleiro preenchido (ver [board]) e as
+/// peças colocadas pela busca (as que já estavam no tabuleiro aparecem apenas nas linhas)
+pub fn solution(solution: &Solution, pieces: &Pieces, topology: Topology) -> Object {
+    let (lin, col) = table::dim(&solution.table);
+    let placements = solution
+        .placements
+        .iter()
+        .map(|p| placement(p, pieces, &solution.table, topology));
+    Object::new()
+        .field("lines", &lin.to_string())
+        .field("columns", &col.to_string())
+        .field("board", &board(&solution.table))
+        .field("pieces", &array(placements))
+}
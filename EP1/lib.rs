@@ -0,0 +1,25 @@
+//! Resolvedor de quebra-cabeças de poliominós (e de policubos, em [packing]).
+//! A interface principal está em [solver]; o programa em main.rs é apenas uma casca em volta dela.
+
+pub mod array;
+pub mod backtrack;
+pub mod bitboard;
+pub mod boards;
+pub mod constraints;
+pub mod control;
+pub mod diagnose;
+pub mod dlx;
+pub mod grid;
+pub mod inventory;
+pub mod json;
+pub mod packing;
+pub mod pilha;
+pub mod poliomino;
+pub mod polycube;
+pub mod solver;
+pub mod space;
+pub mod svg;
+pub mod symmetry;
+pub mod table;
+pub mod trace;
+pub mod verify;
@@ -1,193 +1,1145 @@
-mod array;
-mod pentamino;
-mod pilha;
-mod table;
-
-use pentamino::Pentamino;
-use pilha::Pilha;
-use table::{idx, ok, Table};
-
-/// Estado do backtracking
-#[derive(Debug, Default)]
-struct State {
-    /// Índice do pentaminó
-    p_idx: usize,
-    /// Índice da transformação do pentaminó em pentaminos[p_idx]
-    t_idx: usize,
-    /// Posição do pentaminó no tabuleiro
-    pos: (i32, i32),
-    /// Indica se o pentaminó foi colocado no tabuleiro
-    inserted: bool,
-}
-
-/// Verifica se o tabuleiro tem sessenta espaços em vazio
-fn sixty_spaces(table: &Table) -> bool {
-    60 == table.iter().fold(0, |count, line| {
-        count
-            + line
-                .iter()
-                .fold(0, |count, &c| count + if c == ' ' { 1 } else { 0 })
-    })
-}
-
-// Devolve o tabuleiro transposto
-fn transpost(table: Table) -> Table {
-    let (lin, col) = table::dim(&table);
-    let mut new_table = array::new(idx(col));
-    for new_lin in new_table.iter_mut() {
-        *new_lin = array::new::<char>(idx(lin));
-    }
-    for (x, line) in table.into_iter().enumerate() {
-        for (y, c) in line.into_iter().enumerate() {
-            new_table[y][x] = *c;
-        }
-    }
-    new_table
-}
-
-/// Marca a componente que contém (x, y) e devolve o tamanho da componente
-fn dfs(x: i32, y: i32, marc: &mut Box<[Box<[bool]>]>, table: &Table) -> u32 {
-    const DX: [i32; 4] = [1, 0, -1, 0];
-    const DY: [i32; 4] = [0, 1, 0, -1];
-    #[derive(Default)]
-    struct DfsState {
-        x: i32,
-        y: i32,
-        k: usize,
-    }
-    let mut state: Pilha<DfsState> = Pilha::new();
-    state.push(DfsState { x, y, k: 0 });
-    marc[idx(x)][idx(y)] = true;
-    let mut count = 1;
-    while !state.empty() {
-        let cur = state.back();
-        if cur.k == 4 {
-            state.pop();
+extern crate pentaminos;
+
+use pentaminos::backtrack::Strategy;
+use pentaminos::boards;
+use pentaminos::constraints::Constraints;
+use pentaminos::control::{Checkpoint, Control, Stop};
+use pentaminos::diagnose::{self, Diagnosis};
+use pentaminos::grid::Grid;
+use pentaminos::inventory::Inventory;
+use pentaminos::json;
+use pentaminos::packing;
+use pentaminos::pilha::Pilha;
+use pentaminos::poliomino::{self, Pieces};
+use pentaminos::polycube::{self, Polycubes};
+use pentaminos::solver::{self, Engine, Solution, SolveError};
+use pentaminos::space::{self, Space};
+use pentaminos::svg::{self, Drawing};
+use pentaminos::symmetry;
+use pentaminos::table::{self, Table, Topology};
+use pentaminos::trace::{self, Tracer};
+use pentaminos::verify;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Modo de execução do programa
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// Imprime a primeira solução encontrada (ou -1)
+    First,
+    /// Imprime todas as soluções e o total
+    All,
+    /// Imprime apenas o total de soluções
+    Count,
+    /// Verifica se a solução é única a menos das simetrias do tabuleiro, parando ao encontrar
+    /// duas soluções distintas
+    Unique,
+}
+
+/// Número de soluções encontradas
+#[derive(Default)]
+struct Counts {
+    total: u64,
+    /// Soluções distintas a menos das simetrias do tabuleiro
+    distinct: u64,
+}
+
+/// Opções da linha de comando
+struct Options {
+    mode: Mode,
+    engine: Engine,
+    /// Imprime o tempo gasto na busca na saída de erro
+    time: bool,
+    /// Tamanhos dos poliominós livres usados como peças
+    sizes: Box<[usize]>,
+    /// Arquivo com as peças, que substituem as dadas por `sizes`
+    piece_file: Option<String>,
+    /// Proíbe reflexões das peças
+    one_sided: bool,
+    /// Quantas cópias de cada peça usar (ex.: "F2,L2"); por padrão uma de cada
+    inventory: Option<String>,
+    /// As cópias do inventário são apenas um limite: usa qualquer subconjunto delas
+    any: bool,
+    /// Número de threads do backtracking
+    threads: usize,
+    /// Arquivo com as restrições de colocação das peças
+    constraint_file: Option<String>,
+    /// Estratégia do backtracking
+    strategy: Strategy,
+    /// Imprime o número de nós visitados na saída de erro
+    nodes: bool,
+    /// Encontra apenas uma solução de cada classe de soluções equivalentes
+    break_symmetry: bool,
+    /// Gera um tabuleiro aleatório com solução em vez de ler um ("carve" ou "grow")
+    generate: Option<String>,
+    /// Dimensões do retângulo do qual os quadrados são tirados em --generate carve
+    board_box: Option<(usize, usize)>,
+    /// Semente do gerador de tabuleiros
+    seed: u64,
+    /// Número máximo de tabuleiros sorteados pelo gerador
+    tries: u64,
+    /// Quais bordas do tabuleiro se ligam às opostas e a grade do tabuleiro
+    topology: Topology,
+    /// Empacota policubos num espaço 3D em vez de preencher um tabuleiro
+    solid: bool,
+    /// Usa as peças do cubo Soma (implica `solid`)
+    soma: bool,
+    /// Tamanho dos policubos livres usados como peças (implica `solid`)
+    cubes: Option<usize>,
+    /// Imprime as soluções e as estatísticas da busca em JSON
+    json: bool,
+    /// Rastreia os passos da busca na saída de erro ("json" ou "ansi")
+    trace: Option<String>,
+    /// Espera entre os quadros da animação de --trace ansi
+    delay: Duration,
+    /// Para a busca depois de visitar esse número de nós
+    max_nodes: Option<u64>,
+    /// Para a busca depois desse tempo
+    max_time: Option<Duration>,
+    /// Para a busca ao encontrar esse número de soluções
+    max_solutions: Option<u64>,
+    /// Intervalo entre os relatórios de progresso na saída de erro
+    progress: Option<Duration>,
+    /// Arquivo em que o estado da busca é salvo se ela parar antes do fim (inclusive por SIGINT)
+    checkpoint_file: Option<String>,
+    /// Arquivo com o checkpoint de onde a busca continua
+    resume_file: Option<String>,
+    /// Explica por que o tabuleiro não tem solução (ver [diagnose])
+    diagnose: bool,
+    /// Arquivo com o tabuleiro preenchido a ser verificado como solução do tabuleiro lido
+    verify: Option<String>,
+    /// Arquivo em que as soluções encontradas são desenhadas em SVG
+    svg: Option<String>,
+    /// Escreve as letras das peças no SVG
+    labels: bool,
+    /// Número de soluções por linha da galeria do SVG
+    columns: usize,
+}
+
+impl Options {
+    /// Checa se a busca precisa de limites, progresso ou checkpoints (ver [Control])
+    fn controlled(&self) -> bool {
+        self.max_nodes.is_some()
+            || self.max_time.is_some()
+            || self.max_solutions.is_some()
+            || self.progress.is_some()
+            || self.checkpoint_file.is_some()
+            || self.resume_file.is_some()
+    }
+}
+
+// A função da libc é `sighandler_t signal(int signum, sighandler_t handler)`, com sighandler_t
+// = `void (*)(int)`: `int` é i32 nas plataformas do Rust, o tratador é um ponteiro para função
+// `extern "C"` que recebe i32, e o tratador anterior devolvido é um ponteiro (do tamanho de
+// usize), que não é usado
+extern "C" {
+    fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+}
+
+/// Número do sinal enviado pelo Ctrl-C (o mesmo em todos os sistemas POSIX)
+const SIGINT: i32 = 2;
+
+/// Indica que o usuário apertou Ctrl-C durante uma busca com --checkpoint
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Tratador do SIGINT: só grava numa variável atômica estática, sem travas nem alocação, o que
+/// é permitido dentro de um tratador de sinal (async-signal-safe). A busca confere a variável
+/// entre os nós e para, salvando o checkpoint
+extern "C" fn interrupt(_: i32) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+/// Lê a duração em segundos (ex.: "2.5")
+fn seconds(arg: Option<String>, msg: &str) -> Duration {
+    match arg.map(|s| s.parse::<f64>()) {
+        Some(Ok(secs)) if secs > 0.0 && secs.is_finite() => Duration::from_secs_f64(secs),
+        _ => usage(msg),
+    }
+}
+
+/// Imprime como usar o programa e termina a execução
+fn usage(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!(
+        "Uso: ./exec [--all | --count | --unique | --diagnose] [--engine backtrack|dlx|bitboard] \
+         [--strategy first|mrv|rarest] [--time] [--nodes] [--pieces n,m,...] \
+         [--piece-file arquivo] [--one-sided] [--inventory F2,L2,...] [--any] [--threads n] \
+         [--constraints arquivo] [--break-symmetry] [--wrap lines|columns|both] \
+         [--grid square|hex|triangle] [--json] [--trace json|ansi] [--delay ms] \
+         [--max-nodes n] [--max-time s] [--max-solutions n] [--progress s] \
+         [--checkpoint arquivo] [--resume arquivo] [--svg arquivo [--labels] [--columns n]] \
+         < tabuleiro\n       \
+         ./exec --verify solução [opções das peças] [--wrap ...] [--grid ...] < tabuleiro\n       \
+         ./exec --3d|--soma|--cubes n [--all | --count | --unique] [--time] [--nodes] \
+         [--pieces n,m,... | --piece-file arquivo] [--one-sided] < espaço\n       \
+         ./exec --generate carve|grow [--box LxC] [--seed n] [--tries n] [--unique] \
+         [opções das peças]"
+    );
+    std::process::exit(1);
+}
+
+/// Lê as opções dos argumentos da linha de comando
+fn parse_args() -> Options {
+    let mut opts = Options {
+        mode: Mode::First,
+        engine: Engine::Backtrack,
+        time: false,
+        sizes: Box::new([5]),
+        piece_file: None,
+        one_sided: false,
+        inventory: None,
+        any: false,
+        threads: 1,
+        constraint_file: None,
+        strategy: Strategy::First,
+        nodes: false,
+        break_symmetry: false,
+        generate: None,
+        board_box: None,
+        seed: 0,
+        tries: 100000,
+        topology: Topology::PLANE,
+        solid: false,
+        soma: false,
+        cubes: None,
+        json: false,
+        trace: None,
+        delay: Duration::from_millis(200),
+        max_nodes: None,
+        max_time: None,
+        max_solutions: None,
+        progress: None,
+        checkpoint_file: None,
+        resume_file: None,
+        diagnose: false,
+        verify: None,
+        svg: None,
+        labels: false,
+        columns: 4,
+    };
+    let mut delay = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--all" => opts.mode = Mode::All,
+            "-c" | "--count" => opts.mode = Mode::Count,
+            "-u" | "--unique" => opts.mode = Mode::Unique,
+            "-e" | "--engine" => {
+                opts.engine = match args.next().as_deref() {
+                    Some("backtrack") => Engine::Backtrack,
+                    Some("dlx") => Engine::Dlx,
+                    Some("bitboard") => Engine::Bitboard,
+                    _ => usage("Motor inválido, use backtrack, dlx ou bitboard"),
+                }
+            }
+            "-t" | "--time" => opts.time = true,
+            "-n" | "--nodes" => opts.nodes = true,
+            "-b" | "--break-symmetry" => opts.break_symmetry = true,
+            "-w" | "--wrap" => {
+                let (wrap_lines, wrap_columns) = match args.next().as_deref() {
+                    Some("lines") => (true, false),
+                    Some("columns") => (false, true),
+                    Some("both") => (true, true),
+                    _ => usage("Topologia inválida, use lines, columns ou both"),
+                };
+                opts.topology = Topology {
+                    wrap_lines,
+                    wrap_columns,
+                    ..opts.topology
+                };
+            }
+            "--grid" => {
+                opts.topology.grid = match args.next().as_deref() {
+                    Some("square") => Grid::Square,
+                    Some("hex") => Grid::Hex,
+                    Some("triangle") => Grid::Triangle,
+                    _ => usage("Grade inválida, use square, hex ou triangle"),
+                }
+            }
+            "-s" | "--strategy" => {
+                opts.strategy = match args.next().as_deref().and_then(Strategy::from_name) {
+                    Some(strategy) => strategy,
+                    None => usage("Estratégia inválida, use first, mrv ou rarest"),
+                }
+            }
+            "-p" | "--pieces" => {
+                let sizes = args.next().unwrap_or_default();
+                opts.sizes = sizes
+                    .split(',')
+                    .map(|n| match n.trim().parse::<usize>() {
+                        Ok(n) if n > 0 => n,
+                        _ => usage(&format!("Tamanho de peça inválido: {n}")),
+                    })
+                    .collect();
+            }
+            "-f" | "--piece-file" => match args.next() {
+                Some(path) => opts.piece_file = Some(path),
+                None => usage("Faltou o arquivo de peças"),
+            },
+            "-o" | "--one-sided" => opts.one_sided = true,
+            "-i" | "--inventory" => match args.next() {
+                Some(inventory) => opts.inventory = Some(inventory),
+                None => usage("Faltou o inventário"),
+            },
+            "--any" => opts.any = true,
+            "-j" | "--threads" => {
+                opts.threads = match args.next().map(|n| n.parse::<usize>()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => usage("Número de threads inválido"),
+                }
+            }
+            "-r" | "--constraints" => match args.next() {
+                Some(path) => opts.constraint_file = Some(path),
+                None => usage("Faltou o arquivo de restrições"),
+            },
+            "-g" | "--generate" => match args.next().as_deref() {
+                Some(method @ ("carve" | "grow")) => opts.generate = Some(method.to_string()),
+                _ => usage("Gerador inválido, use carve ou grow"),
+            },
+            "--box" => {
+                let dims = args.next().unwrap_or_default();
+                let dims_parsed = dims
+                    .split_once('x')
+                    .map(|(l, c)| (l.parse::<usize>(), c.parse::<usize>()));
+                opts.board_box = match dims_parsed {
+                    Some((Ok(lin), Ok(col)))
+                        if lin > 0 && col > 0 && lin.saturating_mul(col) <= table::MAX_CELLS =>
+                    {
+                        Some((lin, col))
+                    }
+                    _ => usage(&format!("Dimensões inválidas: {dims}")),
+                }
+            }
+            "--seed" => {
+                opts.seed = match args.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(n)) => n,
+                    _ => usage("Semente inválida"),
+                }
+            }
+            "--tries" => {
+                opts.tries = match args.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => usage("Número de tentativas inválido"),
+                }
+            }
+            "--json" => opts.json = true,
+            "--trace" => match args.next().as_deref() {
+                Some(kind @ ("json" | "ansi")) => opts.trace = Some(kind.to_string()),
+                _ => usage("Rastreamento inválido, use json ou ansi"),
+            },
+            "--delay" => {
+                delay = true;
+                opts.delay = match args.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(ms)) => Duration::from_millis(ms),
+                    _ => usage("Espera inválida"),
+                }
+            }
+            "--max-nodes" => {
+                opts.max_nodes = match args.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(n)) if n > 0 => Some(n),
+                    _ => usage("Limite de nós inválido"),
+                }
+            }
+            "--max-time" => opts.max_time = Some(seconds(args.next(), "Limite de tempo inválido")),
+            "--max-solutions" => {
+                opts.max_solutions = match args.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(n)) if n > 0 => Some(n),
+                    _ => usage("Limite de soluções inválido"),
+                }
+            }
+            "--progress" => {
+                opts.progress = Some(seconds(args.next(), "Intervalo de progresso inválido"))
+            }
+            "--checkpoint" => match args.next() {
+                Some(path) => opts.checkpoint_file = Some(path),
+                None => usage("Faltou o arquivo de checkpoint"),
+            },
+            "--resume" => match args.next() {
+                Some(path) => opts.resume_file = Some(path),
+                None => usage("Faltou o arquivo de checkpoint"),
+            },
+            "-d" | "--diagnose" => opts.diagnose = true,
+            "--verify" => match args.next() {
+                Some(path) => opts.verify = Some(path),
+                None => usage("Faltou o arquivo da solução"),
+            },
+            "--svg" => match args.next() {
+                Some(path) => opts.svg = Some(path),
+                None => usage("Faltou o arquivo do SVG"),
+            },
+            "--labels" => opts.labels = true,
+            "--columns" => {
+                opts.columns = match args.next().map(|n| n.parse::<usize>()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => usage("Número de colunas inválido"),
+                }
+            }
+            "--3d" => opts.solid = true,
+            "--soma" => {
+                opts.solid = true;
+                opts.soma = true;
+            }
+            "--cubes" => {
+                opts.solid = true;
+                opts.cubes = match args.next().map(|n| n.parse::<usize>()) {
+                    Some(Ok(n)) if n > 0 => Some(n),
+                    _ => usage("Tamanho de policubo inválido"),
+                }
+            }
+            _ => usage(&format!("Argumento inválido: {arg}")),
+        }
+    }
+    if opts.solid
+        && (opts.engine != Engine::Backtrack
+            || opts.threads > 1
+            || opts.strategy != Strategy::First
+            || opts.break_symmetry
+            || opts.inventory.is_some()
+            || opts.any
+            || opts.constraint_file.is_some()
+            || opts.generate.is_some()
+            || opts.topology != Topology::PLANE)
+    {
+        usage(
+            "No espaço 3D só são suportadas as opções --all, --count, --unique, --time, --nodes, \
+             --pieces, --piece-file e --one-sided",
+        );
+    }
+    if opts.trace.is_some()
+        && (opts.engine != Engine::Backtrack
+            || opts.threads > 1
+            || opts.mode == Mode::Unique
+            || opts.solid
+            || opts.generate.is_some())
+    {
+        usage("--trace só é suportado pelo motor backtrack com uma thread, sem --unique");
+    }
+    if opts.controlled()
+        && (opts.engine != Engine::Backtrack
+            || opts.threads > 1
+            || opts.mode == Mode::Unique
+            || opts.solid
+            || opts.generate.is_some())
+    {
+        usage(
+            "Limites, --progress, --checkpoint e --resume só são suportados pelo motor backtrack \
+             com uma thread, sem --unique",
+        );
+    }
+    if opts.diagnose
+        && (opts.mode != Mode::First
+            || opts.json
+            || opts.trace.is_some()
+            || opts.controlled()
+            || opts.solid
+            || opts.generate.is_some())
+    {
+        usage(
+            "--diagnose não é suportado com --all, --count, --unique, --json, --trace, limites, \
+             checkpoints, no espaço 3D ou no gerador",
+        );
+    }
+    if opts.verify.is_some()
+        && (opts.mode != Mode::First
+            || opts.diagnose
+            || opts.json
+            || opts.trace.is_some()
+            || opts.controlled()
+            || opts.constraint_file.is_some()
+            || opts.solid
+            || opts.generate.is_some())
+    {
+        usage("--verify só é usado com as opções das peças, --inventory, --any, --wrap e --grid");
+    }
+    if opts.svg.is_some()
+        && (opts.mode == Mode::Count
+            || opts.diagnose
+            || opts.verify.is_some()
+            || opts.solid
+            || opts.generate.is_some())
+    {
+        usage("--svg desenha as soluções impressas, sem --count, --diagnose ou --verify");
+    }
+    if (opts.labels || opts.columns != 4) && opts.svg.is_none() {
+        usage("--labels e --columns só são usados com --svg");
+    }
+    if delay && opts.trace.as_deref() != Some("ansi") {
+        usage("--delay só é usado por --trace ansi");
+    }
+    if opts.json && (opts.solid || opts.generate.is_some()) {
+        usage("--json só é suportado na busca no tabuleiro");
+    }
+    if opts.soma && opts.cubes.is_some() {
+        usage("Use apenas uma de --soma e --cubes");
+    }
+    if opts.solid && opts.one_sided && opts.cubes.is_none() {
+        // As peças planas e as do cubo Soma já são usadas apenas com rotações
+        usage("No espaço 3D --one-sided só é usado com --cubes");
+    }
+    if opts.threads > 1 && opts.engine != Engine::Backtrack {
+        usage("--threads só é suportado pelo motor backtrack");
+    }
+    if opts.strategy != Strategy::First && opts.engine != Engine::Backtrack {
+        usage("--strategy só é suportado pelo motor backtrack");
+    }
+    if opts.generate.is_some() && opts.constraint_file.is_some() {
+        usage("--constraints não é suportado pelo gerador");
+    }
+    if opts.board_box.is_some() && opts.generate.as_deref() != Some("carve") {
+        usage("--box só é usado por --generate carve");
+    }
+    opts
+}
+
+/// Devolve as peças usadas na solução, com o número de cópias quando há mais de uma
+/// (ex.: "F2 I L")
+fn used_pieces(table: &Table, pieces: &Pieces) -> String {
+    let mut used = String::new();
+    for p in pieces.iter() {
+        let cells: usize = table
+            .iter()
+            .map(|line| line.iter().filter(|&&c| c == p[0].ident).count())
+            .sum();
+        let copies = cells / p[0].size();
+        if copies > 0 {
+            if !used.is_empty() {
+                used.push(' ');
+            }
+            used.push(p[0].ident);
+            if copies > 1 {
+                used += &copies.to_string();
+            }
+        }
+    }
+    used
+}
+
+/// Imprime o tabuleiro; na grade hexagonal cada linha é deslocada meio hexágono em relação à
+/// anterior
+fn print_table(table: &Table, grid: Grid) {
+    for (x, line) in table.iter().enumerate() {
+        if grid == Grid::Hex {
+            print!("{}", " ".repeat(x));
+        }
+        for c in line.iter() {
+            print!("{c} ");
+        }
+        println!();
+    }
+}
+
+/// Imprime o espaço com as camadas lado a lado, da primeira à última
+fn print_space(space: &Space) {
+    for x in 0..space[0].len() {
+        for (z, layer) in space.iter().enumerate() {
+            if z > 0 {
+                print!("  ");
+            }
+            for c in layer[x].iter() {
+                print!("{c} ");
+            }
+        }
+        println!();
+    }
+}
+
+/// Imprime a falta de soluções no formato do modo de execução
+fn print_none(mode: Mode) {
+    match mode {
+        Mode::First => println!("-1"),
+        Mode::Unique => println!("nenhuma"),
+        Mode::All | Mode::Count => println!("Soluções: 0 (0 distintas)"),
+    }
+}
+
+/// Imprime as provas de que o tabuleiro não tem solução ou, se nenhuma se aplica, o resultado e
+/// o número de nós da busca
+fn print_diagnosis(table: &Table, opts: &solver::Options) {
+    match diagnose::diagnose(table, opts) {
+        Ok(Diagnosis::Impossible(proofs)) => {
+            println!("Sem solução:");
+            for proof in proofs.iter() {
+                println!("  - {proof}");
+            }
+        }
+        Ok(Diagnosis::Searched { nodes, solvable }) => {
+            let result = if solvable {
+                "Com solução: nenhuma prova simples de impossibilidade se aplica, e a busca \
+                 encontrou uma solução"
+            } else {
+                "Sem solução: nenhuma prova simples de impossibilidade se aplica, e foi \
+                 necessária a busca completa"
+            };
+            println!("{result} ({nodes} nós)");
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Verifica se o tabuleiro preenchido do arquivo é uma solução do tabuleiro, imprimindo os
+/// problemas encontrados (e saindo com o código 1 se houver algum)
+fn verify(path: &str, table: &Table, opts: &solver::Options) {
+    let filled = std::fs::read_to_string(path)
+        .map_err(|e| format!("Erro ao ler a solução {path}: {e}"))
+        .and_then(|s| table::parse(&s).map_err(|e| format!("Solução {path}: {e}")))
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+    match verify::verify(table, &filled, opts) {
+        Ok(violations) if violations.is_empty() => println!("Solução válida"),
+        Ok(violations) => {
+            println!("Solução inválida:");
+            for v in violations.iter() {
+                println!("  - {v}");
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Imprime o resultado do modo --unique: "única" ou "múltiplas", seguido das soluções
+/// encontradas (uma ou duas distintas)
+fn print_unique(examples: &[Solution], grid: Grid) {
+    println!(
+        "{}",
+        if examples.len() == 1 {
+            "única"
         } else {
-            let (px, py) = (cur.x + DX[cur.k], cur.y + DY[cur.k]);
-            cur.k += 1;
-            if ok(px, py, table) && !marc[idx(px)][idx(py)] {
-                marc[idx(px)][idx(py)] = true;
-                state.push(DfsState { x: px, y: py, k: 0 });
-                count += 1;
+            "múltiplas"
+        }
+    );
+    for solution in examples.iter() {
+        println!();
+        print_table(&solution.table, grid);
+    }
+}
+
+/// Imprime o resultado da busca em JSON: no modo padrão um único objeto com a solução (ou com
+/// `board` e `pieces` nulos), o número de nós e o tempo da busca; nos demais modos as soluções
+/// impressas (todas com --all, as distintas com --unique), uma por linha, seguidas de uma linha
+/// com os totais, o número de nós e o tempo
+fn print_json(
+    opts: &Options,
+    counts: &Counts,
+    found: &[Solution],
+    pieces: &Pieces,
+    table: &Table,
+    nodes: u64,
+    time: f64,
+) {
+    let stats = |object: json::Object| {
+        object
+            .field("nodes", &nodes.to_string())
+            .field("time", &format!("{time:.3}"))
+            .finish()
+    };
+    if opts.mode == Mode::First {
+        let object = match found.first() {
+            Some(solution) => json::solution(solution, pieces, opts.topology),
+            None => {
+                let (lin, col) = table::dim(table);
+                json::Object::new()
+                    .field("lines", &lin.to_string())
+                    .field("columns", &col.to_string())
+                    .field("board", "null")
+                    .field("pieces", "null")
             }
+        };
+        println!("{}", stats(object));
+        return;
+    }
+    let mut summary = json::Object::new();
+    if opts.mode == Mode::Unique {
+        for solution in found.iter() {
+            println!(
+                "{}",
+                json::solution(solution, pieces, opts.topology).finish()
+            );
         }
+        summary = summary.field("unique", &(found.len() == 1).to_string());
     }
-    count
+    let summary = summary
+        .field("solutions", &counts.total.to_string())
+        .field("distinct", &counts.distinct.to_string());
+    println!("{}", stats(summary));
 }
 
-/// Verifica se todas as componentes conexas de quadrados vazios do tabuleiro são múltiplas de 5,
-/// diminuindo o número de estados do backtracking que iremos passar
-fn good_table(table: &Table) -> bool {
-    let (lin, col) = table::dim(table);
-    let mut marc = array::new::<Box<[bool]>>(idx(lin));
-    for l in marc.iter_mut() {
-        *l = array::new::<bool>(idx(col));
+/// Avisa na saída de erro por que a busca parou antes do fim e, com --checkpoint, salva o seu
+/// estado (e as soluções encontradas até então) para ser continuada com --resume
+fn save_checkpoint(opts: &Options, stop: Stop, checkpoint: Option<Checkpoint>, counts: &Counts) {
+    let reason = match stop {
+        Stop::Nodes => "limite de nós",
+        Stop::Time => "limite de tempo",
+        Stop::Interrupt => "interrompida",
+        Stop::Found if opts.max_solutions.is_some_and(|max| counts.total >= max) => {
+            "limite de soluções"
+        }
+        // O modo padrão para na primeira solução
+        Stop::Found => "",
+    };
+    if !reason.is_empty() {
+        eprintln!("Busca parada antes do fim: {reason}");
     }
-    for i in 0..lin {
-        for j in 0..col {
-            if table[idx(i)][idx(j)] != ' ' {
-                marc[idx(i)][idx(j)] = true;
+    if let (Some(path), Some(mut checkpoint)) = (&opts.checkpoint_file, checkpoint) {
+        checkpoint.total = counts.total;
+        checkpoint.distinct = counts.distinct;
+        if let Err(e) = std::fs::write(path, checkpoint.format()) {
+            eprintln!("Erro ao escrever o checkpoint {path}: {e}");
+            std::process::exit(1);
+        }
+        eprintln!("Checkpoint salvo em {path}");
+    }
+}
+
+/// Desenha as soluções no arquivo SVG, numa galeria se houver mais de uma
+fn save_svg(opts: &Options, path: &str, drawings: &[Drawing]) {
+    if drawings.is_empty() {
+        eprintln!("Nenhuma solução para desenhar em {path}");
+        return;
+    }
+    let style = svg::Style {
+        labels: opts.labels,
+        columns: opts.columns,
+        ..svg::Style::default()
+    };
+    if let Err(e) = std::fs::write(path, svg::render(drawings, &style)) {
+        eprintln!("Erro ao escrever o SVG {path}: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Gera e imprime um tabuleiro aleatório que pode ser preenchido com as peças (com solução
+/// única no modo --unique)
+fn generate(opts: &Options, method: &str, pieces: Pieces, inventory: Inventory) {
+    let cells = inventory.area(&pieces);
+    if cells == 0 {
+        eprintln!("Inventário vazio: nenhuma peça para colocar no tabuleiro");
+        std::process::exit(1);
+    }
+    let method = match (method, opts.board_box) {
+        ("grow", _) => boards::Method::Grow,
+        (_, Some((lin, col))) if lin * col < cells => usage(&format!(
+            "O retângulo {lin}x{col} tem menos que {cells} quadrados"
+        )),
+        (_, Some((lin, col))) => boards::Method::Carve(lin, col),
+        // Um quadrado com cerca de 1/5 dos quadrados bloqueados
+        _ => {
+            let side = (1..).find(|s| 4 * s * s >= 5 * cells).unwrap();
+            boards::Method::Carve(side, side)
+        }
+    };
+    let solver_opts = solver::Options {
+        pieces,
+        inventory: Some(inventory),
+        constraints: None,
+        engine: opts.engine,
+        threads: opts.threads,
+        strategy: opts.strategy,
+        break_symmetry: opts.break_symmetry,
+        topology: opts.topology,
+    };
+    let mut rng = boards::Rng::new(opts.seed);
+    let start = Instant::now();
+    let result = boards::generate(
+        &solver_opts,
+        method,
+        opts.mode == Mode::Unique,
+        &mut rng,
+        opts.tries,
+    );
+    if opts.time {
+        eprintln!("Tempo: {:.3}s", start.elapsed().as_secs_f64());
+    }
+    match result {
+        Some((table, tries)) => {
+            if opts.time {
+                eprintln!("Tentativas: {tries}");
             }
+            print!("{}", table::format(&table));
+        }
+        None => {
+            eprintln!("Nenhum tabuleiro encontrado em {} tentativas", opts.tries);
+            std::process::exit(1);
         }
     }
-    for i in 0..lin {
-        for j in 0..col {
-            if !marc[idx(i)][idx(j)] {
-                let cnt = dfs(i, j, &mut marc, table);
-                if cnt % 5 != 0 {
-                    return false;
+}
+
+/// Empacota os policubos no espaço lido da entrada padrão e imprime o resultado no formato do
+/// modo de execução
+fn pack(opts: &Options) {
+    let mode = opts.mode;
+    let pieces: Polycubes = if opts.soma {
+        polycube::generator::soma()
+    } else if let Some(n) = opts.cubes {
+        polycube::generator::free(n, !opts.one_sided)
+    } else {
+        let pieces = match &opts.piece_file {
+            Some(path) => poliomino::generator::read(path, Grid::Square).unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }),
+            None => poliomino::generator::mixed(&opts.sizes, Grid::Square),
+        };
+        polycube::generator::lift(&pieces)
+    };
+    let space = space::scan().unwrap_or_else(|e| {
+        eprintln!("{}", SolveError::InvalidBoard(e));
+        std::process::exit(1);
+    });
+    let group = space::group(&space, polycube::generator::reflective(&pieces));
+    let mut counts = Counts::default();
+    // Soluções distintas encontradas no modo --unique (no máximo duas)
+    let mut examples: Pilha<Space> = Pilha::new();
+    let start = Instant::now();
+    let result = packing::search(&space, &pieces, |solution| {
+        counts.total += 1;
+        if space::is_canonical(solution, &group) {
+            counts.distinct += 1;
+        }
+        match mode {
+            Mode::First | Mode::All => {
+                if counts.total > 1 {
+                    println!();
                 }
+                print_space(solution);
             }
+            Mode::Unique => {
+                if !examples
+                    .iter()
+                    .any(|e| space::equivalent(e, solution, &group))
+                {
+                    examples.push(solution.clone());
+                }
+            }
+            Mode::Count => {}
+        }
+        mode != Mode::First && (mode != Mode::Unique || examples.size() < 2)
+    });
+    if opts.time {
+        eprintln!("Tempo: {:.3}s", start.elapsed().as_secs_f64());
+    }
+    match result {
+        Ok(nodes) => {
+            if opts.nodes {
+                eprintln!("Nós: {nodes}");
+            }
+            let Counts { total, distinct } = counts;
+            if (mode == Mode::First || mode == Mode::Unique) && total == 0 {
+                print_none(mode);
+            } else if mode == Mode::Unique {
+                println!(
+                    "{}",
+                    if examples.size() == 1 {
+                        "única"
+                    } else {
+                        "múltiplas"
+                    }
+                );
+                for solution in examples.iter() {
+                    println!();
+                    print_space(solution);
+                }
+            } else if mode != Mode::First {
+                if mode == Mode::All {
+                    println!();
+                }
+                println!("Soluções: {total} ({distinct} distintas)");
+            }
+        }
+        Err(SolveError::WrongCellCount { .. }) | Err(SolveError::NoTiling) => print_none(mode),
+        Err(e @ SolveError::InvalidBoard(_)) => {
+            eprintln!("{e}");
+            std::process::exit(1);
         }
     }
-    true
 }
 
 fn main() {
-    let mut table: table::Table = table::scan();
-    if !sixty_spaces(&table) {
-        println!("-1");
+    let opts = parse_args();
+    if opts.solid {
+        pack(&opts);
         return;
     }
-
-    // PERFORMANCE: transpor o tabuleiro para que col <= lin
-    let transposed = table::dim(&table).0 < table::dim(&table).1;
-    if transposed {
-        table = transpost(table);
+    let mode = opts.mode;
+    let mut pieces = match &opts.piece_file {
+        Some(path) => poliomino::generator::read(path, opts.topology.grid).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }),
+        None => poliomino::generator::mixed(&opts.sizes, opts.topology.grid),
+    };
+    if opts.one_sided {
+        pieces = poliomino::generator::one_sided(&pieces, opts.topology.grid);
     }
-
-    let pentaminos: [Box<[Pentamino]>; 12] = pentamino::generator::generate();
-    // Marcar quais pentaminós já foram usados
-    let mut marc = [false; 12];
-    // Contar quantos pentaminós já foram usados
-    let mut count = 0;
-    let mut state: Pilha<State> = Pilha::new();
-    state.push(State {
-        p_idx: 0,
-        t_idx: 0,
-        pos: table::next_empty(0, 0, &table),
-        inserted: false,
+    let inventory = match &opts.inventory {
+        Some(s) => Inventory::parse(s, &pieces, !opts.any).unwrap_or_else(|e| usage(&e)),
+        None => Inventory::all(&pieces, !opts.any),
+    };
+    if let Some(method) = &opts.generate {
+        generate(&opts, method, pieces, inventory);
+        return;
+    }
+    let table = table::scan().unwrap_or_else(|e| {
+        eprintln!("{}", SolveError::InvalidBoard(e));
+        std::process::exit(1);
+    });
+    let constraints = opts.constraint_file.as_ref().map(|path| {
+        Constraints::read(path, &pieces, &table).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        })
     });
+    let solver_opts = solver::Options {
+        pieces: pieces.clone(),
+        inventory: Some(inventory),
+        constraints,
+        engine: opts.engine,
+        threads: opts.threads,
+        strategy: opts.strategy,
+        break_symmetry: opts.break_symmetry,
+        topology: opts.topology,
+    };
+    if let Some(path) = &opts.verify {
+        verify(path, &table, &solver_opts);
+        return;
+    }
+    if opts.diagnose {
+        print_diagnosis(&table, &solver_opts);
+        return;
+    }
 
-    // Loop da backtracking
-    while !state.empty() {
-        let State {
-            p_idx,
-            t_idx,
-            pos: (x, y),
-            inserted,
-        } = state.back();
-        if *inserted {
-            let p = &pentaminos[*p_idx][*t_idx];
-            p.remove(*x, *y, &mut table);
-            *inserted = false;
-            marc[*p_idx] = false;
-            count -= 1;
-            *t_idx += 1
-        } else if *p_idx >= 12 {
-            state.pop();
-        } else if marc[*p_idx] {
-            *p_idx += 1;
-        } else if *t_idx >= pentaminos[*p_idx].len() {
-            *p_idx += 1;
-            *t_idx = 0;
-        } else {
-            let p = &pentaminos[*p_idx][*t_idx];
-            match p.try_put(*x, *y, &mut table) {
-                Ok(()) => {
-                    *inserted = true;
-                    marc[*p_idx] = true;
-                    count += 1;
-                    if count == 12 {
-                        break;
+    // As simetrias do tabuleiro identificam soluções que são rotações ou reflexões uma da outra.
+    // As restrições em geral não são simétricas, então com elas todas as soluções são distintas
+    let group = match &solver_opts.constraints {
+        Some(constraints) if !constraints.is_empty() => Box::new([]),
+        _ => symmetry::group(&table, &pieces, opts.topology),
+    };
+    // Número de soluções impressas, que também garante que as threads não imprimam ao mesmo tempo
+    let printed = Mutex::new(0u64);
+    // Soluções impressas, a serem desenhadas com --svg
+    let drawings: Mutex<Pilha<Drawing>> = Mutex::new(Pilha::new());
+    // Solução encontrada no modo padrão com --json
+    let first: Mutex<Option<Solution>> = Mutex::new(None);
+    // Soluções encontradas antes do checkpoint de --resume
+    let resumed = opts.resume_file.as_ref().map(|path| {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Erro ao ler o checkpoint {path}: {e}"))
+            .and_then(|s| Checkpoint::parse(&s))
+            .unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(1);
+            })
+    });
+    let before = match &resumed {
+        Some(checkpoint) => Counts {
+            total: checkpoint.total,
+            distinct: checkpoint.distinct,
+        },
+        None => Counts::default(),
+    };
+    // Total de soluções até agora, para o limite de soluções e o progresso
+    let found = AtomicU64::new(before.total);
+    let report = |solution: &Solution, counts: &mut Counts| {
+        let size = match solution.class {
+            // Com a quebra de simetria cada solução representa a sua classe
+            Some(size) => {
+                counts.distinct += 1;
+                size
+            }
+            None => {
+                if symmetry::is_canonical(&solution.table, &group) {
+                    counts.distinct += 1;
+                }
+                1
+            }
+        };
+        counts.total += size;
+        let so_far = found.fetch_add(size, Ordering::Relaxed) + size;
+        let limit = opts.max_solutions.is_some_and(|max| so_far >= max);
+        match mode {
+            Mode::First | Mode::All => {
+                let mut printed = printed.lock().unwrap();
+                if mode == Mode::First && *printed > 0 {
+                    // Outra thread já imprimiu a sua solução
+                    return false;
+                }
+                if opts.json && mode == Mode::First {
+                    // Impressa no final, junto com as estatísticas da busca
+                    *first.lock().unwrap() = Some(solution.clone());
+                } else if opts.json {
+                    println!(
+                        "{}",
+                        json::solution(solution, &pieces, opts.topology).finish()
+                    );
+                } else {
+                    if *printed > 0 {
+                        println!();
                     }
-                    if good_table(&table) {
-                        let next = table::next_empty(*x, *y, &table);
-                        state.push(State {
-                            p_idx: 0,
-                            t_idx: 0,
-                            pos: next,
-                            inserted: false,
-                        })
+                    print_table(&solution.table, opts.topology.grid);
+                    if opts.any {
+                        println!("Peças: {}", used_pieces(&solution.table, &pieces));
                     }
                 }
-                Err(()) => *t_idx += 1,
+                if opts.svg.is_some() {
+                    drawings.lock().unwrap().push(Drawing::new(
+                        &solution.table,
+                        &solution.placements,
+                        &pieces,
+                        opts.topology,
+                    ));
+                }
+                *printed += 1;
+            }
+            Mode::Count | Mode::Unique => {}
+        }
+        mode != Mode::First && !limit
+    };
+    let start = Instant::now();
+    // Soluções distintas encontradas no modo --unique (no máximo duas)
+    let mut examples: Box<[Solution]> = Box::new([]);
+    // Por que a busca parou antes do fim e o seu estado, com --max-nodes, --checkpoint etc.
+    let mut stopped: Option<(Stop, Option<Checkpoint>)> = None;
+    let result = if mode == Mode::Unique {
+        solver::distinct(&table, &solver_opts, 2).map(|(found, stats)| {
+            let n = found.len() as u64;
+            examples = found;
+            (
+                Counts {
+                    total: n,
+                    distinct: n,
+                },
+                stats,
+            )
+        })
+    } else {
+        let mut tracer: Option<Box<dyn Tracer>> = opts.trace.as_deref().map(|kind| {
+            let out = std::io::BufWriter::new(std::io::stderr());
+            let tracer: Box<dyn Tracer> = if kind == "json" {
+                Box::new(trace::JsonLines::new(out, pieces.clone()))
+            } else {
+                Box::new(trace::Animation::new(
+                    out,
+                    pieces.clone(),
+                    opts.topology,
+                    opts.delay,
+                ))
             };
+            tracer
+        });
+        let result = if opts.controlled() {
+            let mut progress = |nodes: u64, depth: usize| {
+                let secs = start.elapsed().as_secs_f64();
+                eprintln!(
+                    "Progresso: {nodes} nós ({:.0} nós/s), profundidade {depth}, {} soluções",
+                    nodes as f64 / secs,
+                    found.load(Ordering::Relaxed)
+                );
+            };
+            let mut control = Control::new();
+            control.max_nodes = opts.max_nodes;
+            control.deadline = opts.max_time.map(|t| start + t);
+            control.resume = resumed;
+            if let Some(every) = opts.progress {
+                control.progress = Some((every, &mut progress));
+            }
+            if opts.checkpoint_file.is_some() {
+                // SAFETY: a declaração de `signal` corresponde à da libc (ver acima), e
+                // `interrupt` pode ser chamada a qualquer momento, pois só grava em INTERRUPTED
+                unsafe {
+                    signal(SIGINT, interrupt);
+                }
+                control.interrupt = Some(&INTERRUPTED);
+            }
+            let result = solver::controlled(
+                &table,
+                &solver_opts,
+                &mut control,
+                tracer.as_deref_mut().map(|t| t as &mut dyn Tracer),
+                &report,
+            );
+            stopped = control.stop.zip(Some(control.checkpoint));
+            result
+        } else {
+            match tracer.as_deref_mut() {
+                Some(tracer) => solver::trace(&table, &solver_opts, tracer, &report),
+                None => solver::search(&table, &solver_opts, &report),
+            }
+        };
+        result.map(|(per_thread, stats)| {
+            let mut counts = Counts { ..before };
+            for c in per_thread.iter() {
+                counts.total += c.total;
+                counts.distinct += c.distinct;
+            }
+            (counts, stats)
+        })
+    };
+    if let (Ok((counts, _)), Some((stop, checkpoint))) = (&result, stopped) {
+        save_checkpoint(&opts, stop, checkpoint, counts);
+    }
+    let time = start.elapsed().as_secs_f64();
+    if opts.time {
+        eprintln!("Tempo: {time:.3}s");
+    }
+    if let (Some(path), Ok(_)) = (&opts.svg, &result) {
+        let mut drawings = drawings.into_inner().unwrap();
+        for solution in examples.iter() {
+            drawings.push(Drawing::new(
+                &solution.table,
+                &solution.placements,
+                &pieces,
+                opts.topology,
+            ));
         }
+        save_svg(&opts, path, &drawings.to_array());
     }
 
-    if count == 12 {
-        if transposed {
-            table = transpost(table);
+    match result {
+        Ok((counts, stats)) if opts.json => {
+            if mode == Mode::First {
+                examples = first.into_inner().unwrap().into_iter().collect();
+            }
+            print_json(
+                &opts,
+                &counts,
+                &examples,
+                &pieces,
+                &table,
+                stats.nodes,
+                time,
+            );
         }
-        for line in table.iter() {
-            for c in line.iter() {
-                print!("{c} ");
+        Ok((Counts { total, distinct }, stats)) => {
+            if opts.nodes {
+                eprintln!("Nós: {}", stats.nodes);
+            }
+            if (mode == Mode::First || mode == Mode::Unique) && total == 0 {
+                print_none(mode);
+            } else if mode == Mode::Unique {
+                print_unique(&examples, opts.topology.grid);
+            } else if mode != Mode::First {
+                if mode == Mode::All && total > 0 {
+                    println!();
+                }
+                println!("Soluções: {total} ({distinct} distintas)");
             }
-            println!();
         }
-    } else {
-        println!("-1");
+        Err(SolveError::WrongCellCount { .. }) | Err(SolveError::NoTiling) if opts.json => {
+            print_json(&opts, &Counts::default(), &[], &pieces, &table, 0, time)
+        }
+        Err(SolveError::WrongCellCount { .. }) | Err(SolveError::NoTiling) => print_none(mode),
+        Err(e @ SolveError::InvalidBoard(_)) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
     }
 }
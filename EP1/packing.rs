@@ -0,0 +1,278 @@
+//! Empacotamento de policubos no espaço 3D com backtracking
+//!
+//! Como na estratégia `First` do [crate::backtrack], cada passo cobre o primeiro cubo vazio
+//! (camada a camada e linha a linha) com o cubo (0, 0, 0) de uma orientação de alguma peça
+//! restante. Depois de cada colocação, as componentes conexas de cubos vazios vizinhas à peça
+//! (pelas 6 faces de cada cubo) devem poder ser preenchidas pelas peças restantes.
+//!
+//! Antes da busca o espaço é girado para que o maior eixo seja o das camadas e o menor o das
+//! colunas: assim a busca avança pela menor seção do espaço (como preencher um 6x10 coluna a
+//! coluna em vez de linha a linha).
+
+use super::array;
+use super::backtrack::gcd;
+use super::pilha::Pilha;
+use super::polycube::Polycubes;
+use super::solver::SolveError;
+use super::space::{self, Space, Transform};
+use super::table::idx;
+
+/// Colocação de uma peça no espaço: a orientação pieces\[p_idx\]\[t_idx\] na posição pos
+#[derive(Clone, Copy, Debug, Default)]
+struct Placement {
+    p_idx: usize,
+    t_idx: usize,
+    pos: (i32, i32, i32),
+}
+
+const NEIGHBOURS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// Rotação que ordena os eixos do espaço do maior para o menor
+fn orientation(space: &Space) -> Transform {
+    let (lay, lin, col) = space::dim(space);
+    let d = [lay, lin, col];
+    let mut perm = [0, 1, 2];
+    perm.sort_by_key(|&i| std::cmp::Reverse(d[i]));
+    let mut t = Transform {
+        perm,
+        flip: [false; 3],
+    };
+    if !t.is_rotation() {
+        t.flip[0] = true;
+    }
+    t
+}
+
+/// Devolve o primeiro cubo vazio a partir de `from` (camada a camada e linha a linha), ou None
+/// se o espaço está cheio
+fn next_empty(space: &Space, from: (i32, i32, i32)) -> Option<(i32, i32, i32)> {
+    let (lay, lin, col) = space::dim(space);
+    let start = (from.0 * lin + from.1) * col + from.2;
+    (start..lay * lin * col)
+        .map(|k| (k / (lin * col), k / col % lin, k % col))
+        .find(|&(z, x, y)| space[idx(z)][idx(x)][idx(y)] == ' ')
+}
+
+/// Poda pelas componentes conexas de cubos vazios, com memória reaproveitada entre os nós da
+/// busca (como a do [crate::backtrack])
+struct Pruning {
+    /// mark\[i\] == stamp se o cubo i já foi visitado na verificação atual
+    mark: Box<[u32]>,
+    stamp: u32,
+    stack: Pilha<(i32, i32, i32)>,
+    /// Cubos da componente visitada
+    region: Pilha<(i32, i32, i32)>,
+}
+
+impl Pruning {
+    fn new(space: &Space) -> Pruning {
+        let (lay, lin, col) = space::dim(space);
+        Pruning {
+            mark: array::new(idx(lay * lin * col)),
+            stamp: 0,
+            stack: Pilha::new(),
+            region: Pilha::new(),
+        }
+    }
+
+    fn index(space: &Space, (z, x, y): (i32, i32, i32)) -> usize {
+        let (_, lin, col) = space::dim(space);
+        idx((z * lin + x) * col + y)
+    }
+
+    /// Guarda em `region` a componente de cubos vazios que contém c, se ela ainda não foi
+    /// visitada nesta verificação. Devolve false se já foi
+    fn visit(&mut self, c: (i32, i32, i32), space: &Space) -> bool {
+        if !space::ok(c, space)
+            || space[idx(c.0)][idx(c.1)][idx(c.2)] != ' '
+            || self.mark[Pruning::index(space, c)] == self.stamp
+        {
+            return false;
+        }
+        self.region.clear();
+        self.stack.push(c);
+        self.mark[Pruning::index(space, c)] = self.stamp;
+        while !self.stack.empty() {
+            let (z, x, y) = *self.stack.back();
+            self.stack.pop();
+            self.region.push((z, x, y));
+            for (dz, dx, dy) in NEIGHBOURS {
+                let n = (z + dz, x + dx, y + dy);
+                if space::ok(n, space)
+                    && space[idx(n.0)][idx(n.1)][idx(n.2)] == ' '
+                    && self.mark[Pruning::index(space, n)] != self.stamp
+                {
+                    self.mark[Pruning::index(space, n)] = self.stamp;
+                    self.stack.push(n);
+                }
+            }
+        }
+        true
+    }
+
+    /// Checa se a componente em `region` pode ser preenchida pelas peças restantes: deve ter
+    /// tamanho múltiplo do mdc dos seus tamanhos e ao menos o menor deles e, se couber só uma
+    /// peça, ter a forma de uma delas
+    fn fillable(&self, space: &Space, pieces: &Polycubes, left: &[usize]) -> bool {
+        let (mut g, mut min) = (0, usize::MAX);
+        for (p, &copies) in pieces.iter().zip(left.iter()) {
+            if copies > 0 {
+                g = gcd(g, p[0].size());
+                min = min.min(p[0].size());
+            }
+        }
+        let cnt = self.region.size();
+        if !cnt.is_multiple_of(g) || cnt < min {
+            return false;
+        }
+        // Cada peça tem ao menos `min` cubos, então com menos de 2 * min cubos a componente é
+        // preenchida por uma única peça e deve ter a sua forma. Com mais cubos a forma não é
+        // verificada (a componente é aceita), o que nunca descarta um espaço com solução
+        let single_piece = cnt < 2 * min;
+        if !single_piece {
+            return true;
+        }
+        let &first = self.region.iter().min().unwrap();
+        pieces.iter().zip(left.iter()).any(|(p, &copies)| {
+            copies > 0 && p[0].size() == cnt && p.iter().any(|t| t.fits_in(first, space))
+        })
+    }
+
+    /// Verifica se as componentes de cubos vazios vizinhas à peça colocada podem ser
+    /// preenchidas pelas peças restantes (ver [Pruning::fillable])
+    fn good_space(
+        &mut self,
+        space: &Space,
+        pieces: &Polycubes,
+        left: &[usize],
+        placed: &Placement,
+    ) -> bool {
+        if self.stamp == u32::MAX {
+            for m in self.mark.iter_mut() {
+                *m = 0;
+            }
+            self.stamp = 0;
+        }
+        self.stamp += 1;
+        let p = &pieces[placed.p_idx][placed.t_idx];
+        let (z, x, y) = placed.pos;
+        for k in 0..p.size() {
+            let (dz, dx, dy) = p.cell(k);
+            for (ez, ex, ey) in NEIGHBOURS {
+                let n = (z + dz + ez, x + dx + ex, y + dy + ey);
+                if self.visit(n, space) && !self.fillable(space, pieces, left) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Nível da busca: colocação escolhida e índice da opção (peça e orientação) usada
+#[derive(Clone, Copy, Default)]
+struct Level {
+    placement: Placement,
+    option: usize,
+}
+
+/// Preenche os cubos vazios do espaço usando cada peça exatamente uma vez. Cubos com letras
+/// (peças já colocadas) não são suportados.
+/// Cada vez que o espaço é preenchido, chama `found` com o espaço resolvido; a busca continua
+/// enquanto `found` devolver true.
+/// Devolve o número de nós visitados (peças colocadas)
+pub fn search(
+    space: &Space,
+    pieces: &Polycubes,
+    mut found: impl FnMut(&Space) -> bool,
+) -> Result<u64, SolveError> {
+    if space.iter().any(|layer| {
+        layer
+            .iter()
+            .any(|line| line.iter().any(|&c| c != ' ' && c != '~'))
+    }) {
+        return Err(SolveError::InvalidBoard(
+            "peças já colocadas não são suportadas no espaço".to_string(),
+        ));
+    }
+    let cells = space::empty_cells(space);
+    let area = pieces.iter().map(|p| p[0].size()).sum();
+    if cells != area {
+        return Err(SolveError::WrongCellCount { cells, area });
+    }
+
+    let rotation = orientation(space);
+    let back = rotation.inverse();
+    let mut space = rotation.apply(space);
+    // Todas as colocações possíveis de um cubo: (peça, orientação)
+    let mut options: Pilha<(usize, usize)> = Pilha::new();
+    for (p_idx, p) in pieces.iter().enumerate() {
+        for t_idx in 0..p.len() {
+            options.push((p_idx, t_idx));
+        }
+    }
+    let options = options.to_array();
+    let mut left = array::new::<usize>(pieces.len());
+    for l in left.iter_mut() {
+        *l = 1;
+    }
+    let mut pruning = Pruning::new(&space);
+    let mut state: Pilha<Level> = Pilha::new();
+    let mut nodes = 0;
+    // Cubo a ser coberto e primeira opção a ser tentada nele
+    let mut cell = next_empty(&space, (0, 0, 0));
+    let mut from = 0;
+    loop {
+        match cell {
+            None => {
+                if !found(&back.apply(&space)) {
+                    break;
+                }
+            }
+            Some(pos) => {
+                let mut descended = false;
+                for (option, &(p_idx, t_idx)) in options.iter().enumerate().skip(from) {
+                    let p = &pieces[p_idx][t_idx];
+                    if left[p_idx] == 0 || !p.fits_in(pos, &space) {
+                        continue;
+                    }
+                    let placement = Placement { p_idx, t_idx, pos };
+                    p.put(pos, &mut space);
+                    left[p_idx] -= 1;
+                    nodes += 1;
+                    if !pruning.good_space(&space, pieces, &left, &placement) {
+                        p.remove(pos, &mut space);
+                        left[p_idx] += 1;
+                        continue;
+                    }
+                    state.push(Level { placement, option });
+                    descended = true;
+                    break;
+                }
+                if descended {
+                    cell = next_empty(&space, pos);
+                    from = 0;
+                    continue;
+                }
+            }
+        }
+        // Volta para o último nível e tenta a próxima opção nele
+        if state.empty() {
+            break;
+        }
+        let Level { placement, option } = *state.back();
+        state.pop();
+        pieces[placement.p_idx][placement.t_idx].remove(placement.pos, &mut space);
+        left[placement.p_idx] += 1;
+        cell = Some(placement.pos);
+        from = option + 1;
+    }
+    Ok(nodes)
+}
@@ -8,6 +8,12 @@ pub struct Pilha<T: Default> {
     arr: Box<[T]>,
 }
 
+impl<T: Default> Default for Pilha<T> {
+    fn default() -> Pilha<T> {
+        Pilha::new()
+    }
+}
+
 #[allow(dead_code)]
 impl<T: Default> Pilha<T> {
     pub fn new() -> Pilha<T> {
@@ -42,6 +48,11 @@ impl<T: Default> Pilha<T> {
         }
     }
 
+    /// Esvazia a pilha, mantendo a memória alocada
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
     pub fn back(&mut self) -> &mut T {
         assert!(self.len > 0);
         &mut self.arr[self.len - 1]
@@ -58,4 +69,16 @@ impl<T: Default> Pilha<T> {
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.arr[0..self.len].iter()
     }
+
+    /// Copia os elementos da pilha para um array estático
+    pub fn to_array(&self) -> Box<[T]>
+    where
+        T: Clone,
+    {
+        let mut arr = array::new(self.len);
+        for (idx, i) in self.iter().enumerate() {
+            arr[idx] = i.clone();
+        }
+        arr
+    }
 }
@@ -0,0 +1,107 @@
+//! Modelagem dos poliominós (peças formadas por quadrados, como os pentaminós)
+
+pub mod generator;
+
+use super::table::{dim, idx, Table, Topology};
+
+/// Representa um poliominó que contém os quadrados
+/// (0, 0), (pt\[0\].0, pt\[0\].1), (pt\[1\].0, pt\[1\].1), ...
+/// ident: Identificador do poliominó (Ex.: 'X', 'Y')
+/// down: Tipo do quadrado (0, 0) na grade (ver [crate::grid::Geometry::parity]); o poliominó só
+/// pode ser colocado em quadrados desse tipo (ex.: triângulos para baixo na grade triangular)
+#[derive(Clone, Debug, Default)]
+pub struct Poliomino {
+    pub pt: Box<[(i32, i32)]>,
+    pub ident: char,
+    pub down: bool,
+}
+
+/// Conjunto de peças: pieces\[i\] contém as transformações da i-ésima peça
+pub type Pieces = Box<[Box<[Poliomino]>]>;
+
+/// Colocação de uma peça no tabuleiro: a transformação pieces\[p_idx\]\[t_idx\] na posição pos
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Placement {
+    pub p_idx: usize,
+    pub t_idx: usize,
+    pub pos: (i32, i32),
+}
+
+impl Poliomino {
+    /// Cria o poliominó com os quadrados (0, 0) e pt
+    pub fn new(ident: char, pt: &[(i32, i32)]) -> Poliomino {
+        Poliomino {
+            pt: pt.into(),
+            ident,
+            down: false,
+        }
+    }
+
+    /// Número de quadrados do poliominó
+    pub fn size(&self) -> usize {
+        self.pt.len() + 1
+    }
+
+    /// Quadrado do tabuleiro ocupado pelo quadrado (dx, dy) do poliominó colocado em (x, y),
+    /// dando a volta nas bordas ligadas pela topologia (None se fica fora do tabuleiro)
+    fn square(
+        x: i32,
+        y: i32,
+        (dx, dy): (i32, i32),
+        table: &Table,
+        topology: Topology,
+    ) -> Option<(usize, usize)> {
+        let (x, y) = topology.wrap((x + dx, y + dy), dim(table))?;
+        Some((idx(x), idx(y)))
+    }
+
+    /// Checa se podemos colocar o poliominó no tabuleiro na posição (x, y)
+    pub fn fits_in(&self, x: i32, y: i32, table: &Table, topology: Topology) -> bool {
+        if table[idx(x)][idx(y)] != ' ' || topology.grid.parity((x, y)) != self.down {
+            return false;
+        }
+        for &d in self.pt.iter() {
+            match Poliomino::square(x, y, d, table, topology) {
+                Some((px, py)) if table[px][py] == ' ' => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Tenta colocar o poliominó no tabuleiro na posição (x, y), devolvendo se conseguiu
+    pub fn try_put(&self, x: i32, y: i32, table: &mut Table, topology: Topology) -> bool {
+        if self.fits_in(x, y, table, topology) {
+            table[idx(x)][idx(y)] = self.ident;
+            for &d in self.pt.iter() {
+                let (px, py) = Poliomino::square(x, y, d, table, topology).unwrap();
+                table[px][py] = self.ident
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tira o poliominó da posição (x, y)
+    /// O poliominó deve ter sido colocado nessa posição anteriormente
+    pub fn remove(&self, x: i32, y: i32, table: &mut Table, topology: Topology) {
+        assert_eq!(table[idx(x)][idx(y)], self.ident);
+        table[idx(x)][idx(y)] = ' ';
+        for &d in self.pt.iter() {
+            let (px, py) = Poliomino::square(x, y, d, table, topology).unwrap();
+            assert_eq!(table[px][py], self.ident);
+            table[px][py] = ' '
+        }
+    }
+
+    /// Número de linhas e de colunas ocupadas pelo poliominó
+    pub fn extent(&self) -> (i32, i32) {
+        let (mut x0, mut x1, mut y0, mut y1) = (0, 0, 0, 0);
+        for &(dx, dy) in self.pt.iter() {
+            (x0, x1) = (x0.min(dx), x1.max(dx));
+            (y0, y1) = (y0.min(dy), y1.max(dy));
+        }
+        (x1 - x0 + 1, y1 - y0 + 1)
+    }
+}
@@ -0,0 +1,424 @@
+//! Geração dos poliominós possíveis para serem colocados no backtracking
+
+use super::{Pieces, Poliomino};
+use crate::array;
+use crate::grid::{Geometry, Grid, Square};
+use crate::pilha::Pilha;
+use crate::table;
+
+/// Posição em que fica o quadrado (0, 0) do poliominó ao aplicar as transformações da grade,
+/// que deve ser do tipo do poliominó (ver [Poliomino::down])
+fn origin(p: &Poliomino) -> (i32, i32) {
+    (0, p.down as i32)
+}
+
+/// Aplica a transformação `f` da grade nos quadrados do poliominó, que passam a ser dados em
+/// relação à imagem do quadrado (0, 0)
+fn map(p: &mut Poliomino, g: &dyn Geometry, f: impl Fn((i32, i32)) -> (i32, i32)) {
+    let (ox, oy) = origin(p);
+    let (nx, ny) = f((ox, oy));
+    for pt in p.pt.iter_mut() {
+        let (x, y) = f((ox + pt.0, oy + pt.1));
+        *pt = (x - nx, y - ny);
+    }
+    p.down = g.parity((nx, ny));
+}
+
+/// Rotaciona o poliominó no sentido anti-horário
+fn rotate(p: &mut Poliomino, g: &dyn Geometry) {
+    map(p, g, |c| g.rotate(c));
+}
+
+/// Reflete o poliominó
+fn reflect(p: &mut Poliomino, g: &dyn Geometry) {
+    map(p, g, |c| g.reflect(c));
+}
+
+/// Transforma o poliominó de modo que as x >= 0 e se x == 0, y >= 0 para garantir unicidade dos
+/// poliominós.
+fn normalized(p: &Poliomino, g: &dyn Geometry) -> Poliomino {
+    let mut p: Poliomino = p.clone();
+    let (ox, oy) = origin(&p);
+    let (mut mx, mut my) = (0, i32::MAX);
+    for (x, _) in p.pt.iter() {
+        mx = mx.min(*x);
+    }
+    if mx == 0 {
+        my = my.min(0);
+    }
+    for (x, y) in p.pt.iter() {
+        if *x == mx {
+            my = my.min(*y);
+        }
+    }
+    for (x, y) in p.pt.iter_mut() {
+        *x -= mx;
+        *y -= my;
+        if *x == 0 && *y == 0 {
+            *x = -mx;
+            *y = -my;
+        }
+    }
+    p.down = g.parity((ox + mx, oy + my));
+    p
+}
+
+/// Checa se dois poliominós normalizados ocupam os mesmos quadrados
+fn same_shape(a: &Poliomino, b: &Poliomino) -> bool {
+    a.down == b.down && a.pt.len() == b.pt.len() && a.pt.iter().all(|pt| b.pt.contains(pt))
+}
+
+/// Insere as rotações do poliominó p na pilha, ignorando as que já estão nela
+fn push_rotations(p: &mut Poliomino, pilha: &mut Pilha<Poliomino>, g: &dyn Geometry) {
+    for _ in 0..g.rotations() {
+        let n = normalized(p, g);
+        if !pilha.iter().any(|q| same_shape(q, &n)) {
+            pilha.push(n);
+        }
+        rotate(p, g);
+    }
+}
+
+/// Gera as transformações (rotações e reflexões) distintas do poliominó.
+/// As simetrias do poliominó são detectadas comparando as formas normalizadas, de modo que
+/// transformações que resultam no mesmo poliominó aparecem uma única vez
+fn transformations(mut p: Poliomino, g: &dyn Geometry) -> Box<[Poliomino]> {
+    let mut pilha = Pilha::new();
+    push_rotations(&mut p, &mut pilha, g);
+    reflect(&mut p, g);
+    push_rotations(&mut p, &mut pilha, g);
+    let mut transformations = array::new(pilha.size());
+    for (idx, t) in pilha.iter().enumerate() {
+        transformations[idx] = t.clone();
+    }
+    transformations
+}
+
+/// Gera as rotações distintas do poliominó
+fn rotations(mut p: Poliomino, g: &dyn Geometry) -> Box<[Poliomino]> {
+    let mut pilha = Pilha::new();
+    push_rotations(&mut p, &mut pilha, g);
+    pilha.to_array()
+}
+
+/// Transforma um conjunto de peças livres em peças de um lado só, que podem ser rotacionadas
+/// mas não refletidas: cada peça fica só com as suas rotações e, se a sua imagem no espelho não
+/// for uma delas, a imagem vira uma nova peça (identificada pela letra minúscula, se possível).
+/// Ex.: os 12 pentaminós viram os 18 pentaminós de um lado só
+pub fn one_sided(pieces: &Pieces, grid: Grid) -> Pieces {
+    let g = grid.geometry();
+    let mut result: Pilha<Box<[Poliomino]>> = Pilha::new();
+    for p in pieces.iter() {
+        result.push(rotations(p[0].clone(), g));
+    }
+    for p in pieces.iter() {
+        let mut mirror = p[0].clone();
+        reflect(&mut mirror, g);
+        let mirror = normalized(&mirror, g);
+        if rotations(p[0].clone(), g)
+            .iter()
+            .any(|q| same_shape(q, &mirror))
+        {
+            continue;
+        }
+        let mut mirrors = rotations(mirror, g);
+        let used = |c: char| c == '~' || result.iter().any(|r| r[0].ident == c);
+        let lower = p[0].ident.to_lowercase().next().unwrap();
+        let ident = if used(lower) {
+            (0..).map(label).find(|&c| !used(c)).unwrap()
+        } else {
+            lower
+        };
+        for t in mirrors.iter_mut() {
+            t.ident = ident;
+        }
+        result.push(mirrors);
+    }
+    result.to_array()
+}
+
+/// Checa se a imagem no espelho de cada peça é uma das suas transformações (isto é, se as peças
+/// podem ser refletidas)
+pub fn reflective(pieces: &Pieces, grid: Grid) -> bool {
+    let g = grid.geometry();
+    pieces.iter().all(|p| {
+        let mut mirror = p[0].clone();
+        reflect(&mut mirror, g);
+        let mirror = normalized(&mirror, g);
+        p.iter().any(|q| same_shape(q, &mirror))
+    })
+}
+
+/// Devolve o conjunto de peças transposto (trocando as coordenadas x e y de cada quadrado),
+/// mantendo os índices das peças e das transformações. Assim resolver o tabuleiro transposto com
+/// as peças transpostas equivale a resolver o tabuleiro original, mesmo sem reflexões.
+/// Só serve para as grades em que transpor preserva os vizinhos (quadrada e hexagonal)
+pub fn transposed(pieces: &Pieces) -> Pieces {
+    let mut result = pieces.clone();
+    for transformations in result.iter_mut() {
+        for t in transformations.iter_mut() {
+            for pt in t.pt.iter_mut() {
+                (pt.0, pt.1) = (pt.1, pt.0);
+            }
+            *t = normalized(t, &Square);
+        }
+    }
+    result
+}
+
+/// Gera os 5 tetraminós e respectivas possíveis transformações
+pub fn tetrominoes() -> Pieces {
+    const I: [(i32, i32); 3] = [(0, 1), (0, 2), (0, 3)];
+    const O: [(i32, i32); 3] = [(0, 1), (1, 0), (1, 1)];
+    const T: [(i32, i32); 3] = [(0, 1), (0, 2), (1, 1)];
+    const L: [(i32, i32); 3] = [(0, 1), (0, 2), (1, 0)];
+    const S: [(i32, i32); 3] = [(0, 1), (1, 0), (1, -1)];
+    Box::new([
+        transformations(Poliomino::new('L', &L), &Square),
+        transformations(Poliomino::new('S', &S), &Square),
+        transformations(Poliomino::new('I', &I), &Square),
+        transformations(Poliomino::new('T', &T), &Square),
+        transformations(Poliomino::new('O', &O), &Square),
+    ])
+}
+
+/// Gera os 12 pentaminós e respectivas possíveis transformações
+pub fn generate() -> Pieces {
+    const I: [(i32, i32); 4] = [(0, 1), (0, 2), (0, -1), (0, -2)];
+    const F: [(i32, i32); 4] = [(0, -1), (1, 1), (0, 1), (-1, 0)];
+    const N: [(i32, i32); 4] = [(0, -1), (1, 0), (1, 1), (1, 2)];
+    const P: [(i32, i32); 4] = [(0, 1), (0, 2), (1, 1), (1, 2)];
+    const Y: [(i32, i32); 4] = [(1, 0), (0, 1), (0, -1), (0, -2)];
+    const W: [(i32, i32); 4] = [(0, -1), (1, 0), (1, 1), (-1, -1)];
+    const Z: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 1), (-1, -1)];
+    const V: [(i32, i32); 4] = [(1, 0), (-1, 0), (1, 1), (1, 2)];
+    const L: [(i32, i32); 4] = [(0, 1), (0, 2), (0, 3), (1, 0)];
+    const U: [(i32, i32); 4] = [(1, 0), (-1, 0), (1, 1), (-1, 1)];
+    const T: [(i32, i32); 4] = [(0, 1), (0, 2), (-1, 2), (1, 2)];
+    const X: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    Box::new([
+        transformations(Poliomino::new('F', &F), &Square),
+        transformations(Poliomino::new('N', &N), &Square),
+        transformations(Poliomino::new('P', &P), &Square),
+        transformations(Poliomino::new('Y', &Y), &Square),
+        transformations(Poliomino::new('L', &L), &Square),
+        transformations(Poliomino::new('Z', &Z), &Square),
+        transformations(Poliomino::new('W', &W), &Square),
+        transformations(Poliomino::new('I', &I), &Square),
+        transformations(Poliomino::new('V', &V), &Square),
+        transformations(Poliomino::new('U', &U), &Square),
+        transformations(Poliomino::new('T', &T), &Square),
+        transformations(Poliomino::new('X', &X), &Square),
+    ])
+}
+
+/// Devolve o k-ésimo identificador usado para rotular peças geradas automaticamente (sem '0'
+/// e '1', que no tabuleiro são quadrados vazios ou que não podem ser ocupados)
+pub(crate) fn label(k: usize) -> char {
+    const LABELS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz23456789";
+    match LABELS.chars().nth(k) {
+        Some(c) => c,
+        // Letras latinas com acento, gregas, ...
+        None => char::from_u32(0xc0 + (k - LABELS.len()) as u32).unwrap(),
+    }
+}
+
+/// Gera todos os poliominós livres (isto é, a menos de rotações e reflexões) com n quadrados
+/// na grade dada, crescendo cada poliominó com n - 1 quadrados por um quadrado vizinho.
+/// Ex.: na grade hexagonal são os polihexes e na triangular, os poliamantes
+pub fn free(n: usize, grid: Grid) -> Pieces {
+    assert!(n > 0, "Poliominós devem ter ao menos um quadrado");
+    let g = grid.geometry();
+    let mut pieces: Pilha<Box<[Poliomino]>> = Pilha::new();
+    pieces.push(transformations(Poliomino::new('A', &[]), g));
+    for _ in 1..n {
+        let mut bigger: Pilha<Box<[Poliomino]>> = Pilha::new();
+        for p in pieces.iter() {
+            let p = &p[0];
+            let (ox, oy) = origin(p);
+            let mut cells = array::new::<(i32, i32)>(p.size());
+            cells[1..].copy_from_slice(&p.pt);
+            for &(x, y) in cells.iter() {
+                for &(dx, dy) in g.neighbours((ox + x, oy + y)) {
+                    let c = (x + dx, y + dy);
+                    if cells.contains(&c) {
+                        continue;
+                    }
+                    let mut pt = array::new::<(i32, i32)>(p.pt.len() + 1);
+                    pt[1..].copy_from_slice(&p.pt);
+                    pt[0] = c;
+                    let q = Poliomino {
+                        pt,
+                        ident: 'A',
+                        down: p.down,
+                    };
+                    let q = normalized(&q, g);
+                    if !bigger.iter().any(|t| t.iter().any(|o| same_shape(o, &q))) {
+                        bigger.push(transformations(q, g));
+                    }
+                }
+            }
+        }
+        pieces = bigger;
+    }
+    let mut pieces = pieces.to_array();
+    for (k, transformations) in pieces.iter_mut().enumerate() {
+        for t in transformations.iter_mut() {
+            t.ident = label(k);
+        }
+    }
+    pieces
+}
+
+/// Lê um conjunto de peças de um texto em que cada peça é dada por uma linha com o seu
+/// identificador seguida do desenho da peça, com '#' nos quadrados ocupados e '.' nos vazios.
+/// As peças são separadas por linhas em branco e linhas começando com "//" são ignoradas.
+/// Ex.:
+/// ```text
+/// // Pentaminó F
+/// F
+/// .##
+/// ##.
+/// .#.
+/// ```
+/// Nas outras grades o desenho usa as coordenadas do tabuleiro (ver [crate::grid])
+pub fn parse(s: &str, grid: Grid) -> Result<Pieces, String> {
+    let g = grid.geometry();
+    let mut pieces: Pilha<Box<[Poliomino]>> = Pilha::new();
+    // Identificador e linha da peça sendo lida
+    let mut ident: Option<(char, usize)> = None;
+    let mut cells: Pilha<(i32, i32)> = Pilha::new();
+    let mut row = 0;
+    // Uma linha em branco extra no final para terminar a última peça
+    for (num, line) in s.lines().chain(std::iter::once("")).enumerate() {
+        let num = num + 1;
+        let line = line.trim_end();
+        if line.starts_with("//") {
+            continue;
+        }
+        match ident {
+            None if line.is_empty() => {}
+            None => {
+                let mut chars = line.trim().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if table::is_piece(c) => {
+                        if pieces.iter().any(|p| p[0].ident == c) {
+                            return Err(format!("Linha {num}: peça {c} repetida"));
+                        }
+                        ident = Some((c, num));
+                        cells = Pilha::new();
+                        row = 0;
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Linha {num}: esperado o identificador da peça (um caractere \
+                             diferente de '0', '.', '1', '#' e '~'), encontrado \"{line}\""
+                        ))
+                    }
+                }
+            }
+            Some((c, start)) if line.is_empty() => {
+                if cells.empty() {
+                    return Err(format!("Linha {start}: peça {c} sem quadrados"));
+                }
+                let mut pt = array::new::<(i32, i32)>(cells.size() - 1);
+                let (ax, ay) = *cells.iter().next().unwrap();
+                for (k, &(x, y)) in cells.iter().skip(1).enumerate() {
+                    pt[k] = (x - ax, y - ay);
+                }
+                let p = Poliomino {
+                    pt,
+                    ident: c,
+                    down: g.parity((ax, ay)),
+                };
+                if !connected(&p, grid) {
+                    return Err(format!("Linha {start}: peça {c} não é conexa"));
+                }
+                pieces.push(transformations(p, g));
+                ident = None;
+            }
+            Some(_) => {
+                for (col, b) in line.chars().enumerate() {
+                    match b {
+                        '#' => cells.push((row, col as i32)),
+                        '.' | ' ' => {}
+                        _ => {
+                            return Err(format!(
+                                "Linha {num}, coluna {}: caractere inválido '{b}' (use '#' ou '.')",
+                                col + 1
+                            ))
+                        }
+                    }
+                }
+                row += 1;
+            }
+        }
+    }
+    if pieces.empty() {
+        return Err("Nenhuma peça encontrada".to_string());
+    }
+    Ok(pieces.to_array())
+}
+
+/// Lê o conjunto de peças do arquivo em `path` (veja [parse])
+pub fn read(path: &str, grid: Grid) -> Result<Pieces, String> {
+    let s = std::fs::read_to_string(path).map_err(|e| format!("Erro ao ler {path}: {e}"))?;
+    parse(&s, grid).map_err(|e| format!("{path}: {e}"))
+}
+
+/// Checa se os quadrados do poliominó formam uma única componente conexa na grade
+fn connected(p: &Poliomino, grid: Grid) -> bool {
+    let (ox, oy) = origin(p);
+    let mut cells = array::new::<(i32, i32)>(p.size());
+    cells[0] = (ox, oy);
+    for (k, &(dx, dy)) in p.pt.iter().enumerate() {
+        cells[k + 1] = (ox + dx, oy + dy);
+    }
+    let mut marc = array::new::<bool>(p.size());
+    let mut pilha = Pilha::new();
+    marc[0] = true;
+    pilha.push(0usize);
+    let mut count = 1;
+    while !pilha.empty() {
+        let (x, y) = cells[*pilha.back()];
+        pilha.pop();
+        for (k, &(a, b)) in cells.iter().enumerate() {
+            if !marc[k] && grid.adjacent((x, y), (a, b)) {
+                marc[k] = true;
+                pilha.push(k);
+                count += 1;
+            }
+        }
+    }
+    count == p.size()
+}
+
+/// Gera os poliominós livres com n quadrados na grade dada, usando os nomes usuais dos
+/// tetraminós e pentaminós
+pub fn polyominoes(n: usize, grid: Grid) -> Pieces {
+    match (n, grid) {
+        (4, Grid::Square) => tetrominoes(),
+        (5, Grid::Square) => generate(),
+        _ => free(n, grid),
+    }
+}
+
+/// Junta os poliominós livres de cada um dos tamanhos dados num único conjunto de peças,
+/// renomeando peças de tamanhos diferentes que tenham o mesmo identificador
+pub fn mixed(sizes: &[usize], grid: Grid) -> Pieces {
+    let mut pieces: Pilha<Box<[Poliomino]>> = Pilha::new();
+    for &n in sizes.iter() {
+        for p in polyominoes(n, grid).iter() {
+            let mut transformations = p.clone();
+            let used = |c: char| pieces.iter().any(|p| p[0].ident == c);
+            if used(transformations[0].ident) {
+                let ident = (0..).map(label).find(|&c| !used(c)).unwrap();
+                for t in transformations.iter_mut() {
+                    t.ident = ident;
+                }
+            }
+            pieces.push(transformations);
+        }
+    }
+    pieces.to_array()
+}
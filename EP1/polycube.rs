@@ -0,0 +1,71 @@
+//! Modelagem dos policubos (peças formadas por cubos, como as do cubo Soma)
+
+pub mod generator;
+
+use super::space::Space;
+use super::table::idx;
+
+/// Representa um policubo que contém os cubos
+/// (0, 0, 0), (pt\[0\].0, pt\[0\].1, pt\[0\].2), ... nas coordenadas (camada, linha, coluna).
+/// O cubo (0, 0, 0) é o primeiro do policubo, camada a camada e linha a linha
+/// ident: Identificador do policubo (Ex.: 'V', 'A')
+#[derive(Clone, Debug, Default)]
+pub struct Polycube {
+    pub pt: Box<[(i32, i32, i32)]>,
+    pub ident: char,
+}
+
+/// Conjunto de peças: pieces\[i\] contém as orientações da i-ésima peça
+pub type Polycubes = Box<[Box<[Polycube]>]>;
+
+impl Polycube {
+    /// Cria o policubo com os cubos (0, 0, 0) e pt
+    pub fn new(ident: char, pt: &[(i32, i32, i32)]) -> Polycube {
+        Polycube {
+            pt: pt.into(),
+            ident,
+        }
+    }
+
+    /// Número de cubos do policubo
+    pub fn size(&self) -> usize {
+        self.pt.len() + 1
+    }
+
+    /// k-ésimo cubo do policubo (o 0 é o (0, 0, 0))
+    pub fn cell(&self, k: usize) -> (i32, i32, i32) {
+        if k == 0 {
+            (0, 0, 0)
+        } else {
+            self.pt[k - 1]
+        }
+    }
+
+    /// Checa se podemos colocar o policubo no espaço na posição (z, x, y)
+    pub fn fits_in(&self, (z, x, y): (i32, i32, i32), space: &Space) -> bool {
+        (0..self.size()).all(|k| {
+            let (dz, dx, dy) = self.cell(k);
+            let (cz, cx, cy) = (z + dz, x + dx, y + dy);
+            super::space::ok((cz, cx, cy), space) && space[idx(cz)][idx(cx)][idx(cy)] == ' '
+        })
+    }
+
+    /// Coloca o policubo na posição (z, x, y), que deve ter sido checada por [Polycube::fits_in]
+    pub fn put(&self, (z, x, y): (i32, i32, i32), space: &mut Space) {
+        for k in 0..self.size() {
+            let (dz, dx, dy) = self.cell(k);
+            space[idx(z + dz)][idx(x + dx)][idx(y + dy)] = self.ident;
+        }
+    }
+
+    /// Tira o policubo da posição (z, x, y)
+    /// O policubo deve ter sido colocado nessa posição anteriormente
+    pub fn remove(&self, (z, x, y): (i32, i32, i32), space: &mut Space) {
+        for k in 0..self.size() {
+            let (dz, dx, dy) = self.cell(k);
+            let c = &mut space[idx(z + dz)][idx(x + dx)][idx(y + dy)];
+            assert_eq!(*c, self.ident);
+            *c = ' ';
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Geração dos policubos e das suas orientações
+
+use super::{Polycube, Polycubes};
+use crate::array;
+use crate::pilha::Pilha;
+use crate::poliomino::generator::label;
+use crate::poliomino::Pieces;
+
+type Cube = (i32, i32, i32);
+
+/// As 48 simetrias do cubo, como uma permutação dos eixos e os sinais de cada eixo da imagem;
+/// as 24 primeiras são as rotações
+fn orientations() -> Box<[([usize; 3], [i32; 3])]> {
+    const PERMS: [([usize; 3], bool); 6] = [
+        ([0, 1, 2], false),
+        ([1, 2, 0], false),
+        ([2, 0, 1], false),
+        ([0, 2, 1], true),
+        ([1, 0, 2], true),
+        ([2, 1, 0], true),
+    ];
+    let mut rotations = Pilha::new();
+    let mut reflections = Pilha::new();
+    for (perm, odd) in PERMS {
+        for k in 0..8 {
+            let sign = [
+                if k & 4 != 0 { -1 } else { 1 },
+                if k & 2 != 0 { -1 } else { 1 },
+                if k & 1 != 0 { -1 } else { 1 },
+            ];
+            // O determinante da transformação é o sinal da permutação vezes os sinais
+            if odd ^ (sign[0] * sign[1] * sign[2] < 0) {
+                reflections.push((perm, sign));
+            } else {
+                rotations.push((perm, sign));
+            }
+        }
+    }
+    let mut all = array::new(48);
+    for (i, o) in rotations.iter().chain(reflections.iter()).enumerate() {
+        all[i] = *o;
+    }
+    all
+}
+
+/// Devolve os cubos do policubo, começando pelo (0, 0, 0)
+fn cells(p: &Polycube) -> Box<[Cube]> {
+    let mut cells = array::new::<Cube>(p.size());
+    for (k, c) in cells.iter_mut().enumerate() {
+        *c = p.cell(k);
+    }
+    cells
+}
+
+/// Policubo com os cubos dados, transladados para que o primeiro (camada a camada e linha a
+/// linha) seja o (0, 0, 0); os demais ficam ordenados, para garantir unicidade
+fn normalized(ident: char, cells: &[Cube]) -> Polycube {
+    let mut sorted: Box<[Cube]> = cells.into();
+    sorted.sort();
+    let (az, ax, ay) = sorted[0];
+    let mut pt = array::new::<Cube>(sorted.len() - 1);
+    for (k, &(z, x, y)) in sorted.iter().skip(1).enumerate() {
+        pt[k] = (z - az, x - ax, y - ay);
+    }
+    Polycube { pt, ident }
+}
+
+/// Aplica a simetria do cubo nos cubos do policubo
+fn transform(p: &Polycube, (perm, sign): ([usize; 3], [i32; 3])) -> Polycube {
+    let mut cells = cells(p);
+    for c in cells.iter_mut() {
+        let v = [c.0, c.1, c.2];
+        *c = (
+            sign[0] * v[perm[0]],
+            sign[1] * v[perm[1]],
+            sign[2] * v[perm[2]],
+        );
+    }
+    normalized(p.ident, &cells)
+}
+
+/// Gera as orientações distintas do policubo: as rotações e, se `reflections`, também as
+/// reflexões. Orientações que resultam no mesmo policubo aparecem uma única vez
+pub fn transformations(p: &Polycube, reflections: bool) -> Box<[Polycube]> {
+    let count = if reflections { 48 } else { 24 };
+    let mut pilha: Pilha<Polycube> = Pilha::new();
+    for &o in orientations().iter().take(count) {
+        let t = transform(p, o);
+        if !pilha.iter().any(|q| q.pt == t.pt) {
+            pilha.push(t);
+        }
+    }
+    pilha.to_array()
+}
+
+/// Transforma peças planas (poliominós) em policubos de uma camada, que podem ser giradas no
+/// espaço (e assim também viradas do avesso). Ex.: os 12 pentaminós viram os 12 pentacubos
+/// planos
+pub fn lift(pieces: &Pieces) -> Polycubes {
+    let mut result = array::new::<Box<[Polycube]>>(pieces.len());
+    for (r, p) in result.iter_mut().zip(pieces.iter()) {
+        let mut cells = array::new::<Cube>(p[0].size());
+        for (c, &(x, y)) in cells[1..].iter_mut().zip(p[0].pt.iter()) {
+            *c = (0, x, y);
+        }
+        *r = transformations(&normalized(p[0].ident, &cells), false);
+    }
+    result
+}
+
+/// Gera as 7 peças do cubo Soma e as suas rotações (A e B são imagens no espelho uma da outra,
+/// então as peças não podem ser refletidas)
+pub fn soma() -> Polycubes {
+    const V: [Cube; 2] = [(0, 0, 1), (0, 1, 0)];
+    const L: [Cube; 3] = [(0, 0, 1), (0, 0, 2), (0, 1, 0)];
+    const T: [Cube; 3] = [(0, 0, 1), (0, 0, 2), (0, 1, 1)];
+    const Z: [Cube; 3] = [(0, 0, 1), (0, 1, 1), (0, 1, 2)];
+    const A: [Cube; 3] = [(0, 0, 1), (0, 1, 0), (1, 0, 1)];
+    const B: [Cube; 3] = [(0, 0, 1), (0, 1, 0), (1, 1, 0)];
+    const P: [Cube; 3] = [(0, 0, 1), (0, 1, 0), (1, 0, 0)];
+    Box::new([
+        transformations(&Polycube::new('V', &V), false),
+        transformations(&Polycube::new('L', &L), false),
+        transformations(&Polycube::new('T', &T), false),
+        transformations(&Polycube::new('Z', &Z), false),
+        transformations(&Polycube::new('A', &A), false),
+        transformations(&Polycube::new('B', &B), false),
+        transformations(&Polycube::new('P', &P), false),
+    ])
+}
+
+/// Gera todos os policubos com n cubos a menos de rotações (e de reflexões, se `reflections`),
+/// crescendo cada policubo com n - 1 cubos por um cubo vizinho
+pub fn free(n: usize, reflections: bool) -> Polycubes {
+    const NEIGHBOURS: [Cube; 6] = [
+        (1, 0, 0),
+        (-1, 0, 0),
+        (0, 1, 0),
+        (0, -1, 0),
+        (0, 0, 1),
+        (0, 0, -1),
+    ];
+    assert!(n > 0, "Policubos devem ter ao menos um cubo");
+    let mut pieces: Pilha<Box<[Polycube]>> = Pilha::new();
+    pieces.push(transformations(&Polycube::new('A', &[]), reflections));
+    for _ in 1..n {
+        let mut bigger: Pilha<Box<[Polycube]>> = Pilha::new();
+        for p in pieces.iter() {
+            let cells = cells(&p[0]);
+            for &(z, x, y) in cells.iter() {
+                for (dz, dx, dy) in NEIGHBOURS {
+                    let c = (z + dz, x + dx, y + dy);
+                    if cells.contains(&c) {
+                        continue;
+                    }
+                    let mut grown = array::new::<Cube>(cells.len() + 1);
+                    grown[1..].copy_from_slice(&cells);
+                    grown[0] = c;
+                    let q = normalized('A', &grown);
+                    if !bigger.iter().any(|t| t.iter().any(|o| o.pt == q.pt)) {
+                        bigger.push(transformations(&q, reflections));
+                    }
+                }
+            }
+        }
+        pieces = bigger;
+    }
+    let mut pieces = pieces.to_array();
+    for (k, transformations) in pieces.iter_mut().enumerate() {
+        for t in transformations.iter_mut() {
+            t.ident = label(k);
+        }
+    }
+    pieces
+}
+
+/// Checa se a imagem no espelho de cada peça é uma das suas orientações (isto é, se as peças
+/// podem ser refletidas)
+pub fn reflective(pieces: &Polycubes) -> bool {
+    let mirror = orientations()[24];
+    pieces.iter().all(|p| {
+        let m = transform(&p[0], mirror);
+        p.iter().any(|q| q.pt == m.pt)
+    })
+}
@@ -0,0 +1,566 @@
+//! Interface de biblioteca do resolvedor: recebe o tabuleiro e as opções e devolve as soluções
+//! como listas de colocações de peças, sem ler da entrada nem imprimir nada.
+//!
+//! Ex.:
+//! ```ignore
+//! let table = table::parse("0 0 0 0 0\n0 0 0 0 0")?;
+//! let pieces = poliomino::generator::generate();
+//! let solution = solver::solve(&table, &solver::Options::new(pieces));
+//! ```
+
+use super::array;
+use super::backtrack::{self, Strategy};
+use super::bitboard;
+use super::constraints::Constraints;
+use super::control::{Checkpoint, Control};
+use super::dlx;
+use super::grid::Grid;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::{generator, Pieces, Placement};
+use super::symmetry;
+use super::table::{self, Table, Topology};
+use super::trace::{NoTrace, Tracer};
+use std::sync::Mutex;
+
+/// Algoritmo usado para resolver o tabuleiro
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Engine {
+    /// Backtracking em [backtrack::search]
+    Backtrack,
+    /// Dancing Links em [dlx::search]
+    Dlx,
+    /// Backtracking com o tabuleiro em bits em [bitboard::search] (tabuleiros maiores que
+    /// [bitboard::MAX_CELLS] quadrados ou com grade não quadrada usam o [backtrack::search])
+    Bitboard,
+}
+
+/// Opções do resolvedor
+pub struct Options {
+    /// Peças e suas transformações (ver [generator])
+    pub pieces: Pieces,
+    /// Quantas cópias de cada peça usar; `None` usa uma cópia de cada peça
+    pub inventory: Option<Inventory>,
+    /// Onde e em quais transformações cada peça pode ser colocada; `None` não restringe nada
+    pub constraints: Option<Constraints>,
+    pub engine: Engine,
+    /// Número de threads (apenas no backtracking)
+    pub threads: usize,
+    /// Estratégia de escolha do próximo passo (apenas no backtracking)
+    pub strategy: Strategy,
+    /// Encontra apenas uma solução de cada classe de soluções equivalentes pelas simetrias do
+    /// tabuleiro (ver [symmetry::break_symmetry]); ignorado se há restrições, bordas ligadas ou a
+    /// grade não é quadrada
+    pub break_symmetry: bool,
+    /// Quais bordas do tabuleiro se ligam às opostas, permitindo que as peças deem a volta, e a
+    /// grade do tabuleiro e das peças
+    pub topology: Topology,
+}
+
+impl Options {
+    /// Uma cópia de cada uma das peças, resolvida pelo backtracking com uma thread
+    pub fn new(pieces: Pieces) -> Options {
+        Options {
+            pieces,
+            inventory: None,
+            constraints: None,
+            engine: Engine::Backtrack,
+            threads: 1,
+            strategy: Strategy::First,
+            break_symmetry: false,
+            topology: Topology::PLANE,
+        }
+    }
+}
+
+/// Uma solução do tabuleiro
+#[derive(Clone, Debug, Default)]
+pub struct Solution {
+    /// Tabuleiro preenchido com os identificadores das peças
+    pub table: Table,
+    /// Peças colocadas: a transformação pieces\[p_idx\]\[t_idx\] com o quadrado (0, 0) em pos
+    /// (as peças que já estavam no tabuleiro não aparecem)
+    pub placements: Box<[Placement]>,
+    /// Com a quebra de simetria, o número de soluções equivalentes a esta (incluindo ela mesma),
+    /// que não são encontradas
+    pub class: Option<u64>,
+}
+
+/// Estatísticas da busca
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stats {
+    /// Número de nós visitados: peças colocadas no backtracking ou linhas escolhidas no
+    /// Dancing Links
+    pub nodes: u64,
+}
+
+/// Motivo pelo qual o tabuleiro não foi resolvido
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveError {
+    /// O tabuleiro não pode ser lido ou não é retangular
+    InvalidBoard(String),
+    /// O número de quadrados vazios não corresponde à área das peças do inventário (deve ser
+    /// igual, ou no máximo igual se nem todas as peças precisam ser usadas)
+    WrongCellCount { cells: usize, area: usize },
+    /// Não existe maneira de cobrir o tabuleiro com as peças
+    NoTiling,
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolveError::InvalidBoard(msg) => write!(f, "Tabuleiro inválido: {msg}"),
+            SolveError::WrongCellCount { cells, area } => write!(
+                f,
+                "O tabuleiro tem {cells} quadrados vazios, mas as peças têm {area} quadrados"
+            ),
+            SolveError::NoTiling => write!(f, "Não existe solução"),
+        }
+    }
+}
+
+/// Checa se o tabuleiro tem ao menos uma linha e todas as linhas têm o mesmo tamanho
+pub fn validate(table: &Table) -> Result<(), SolveError> {
+    if table.is_empty() || table[0].is_empty() {
+        return Err(SolveError::InvalidBoard("tabuleiro vazio".to_string()));
+    }
+    for (num, line) in table.iter().enumerate() {
+        if line.len() != table[0].len() {
+            return Err(SolveError::InvalidBoard(format!(
+                "linhas 0 e {num} possuem um número de colunas diferentes"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Desconta do inventário as peças que já estão no tabuleiro (quadrados com o identificador de
+/// uma peça), que não são movidas pela busca. Um identificador que não é de nenhuma peça é
+/// indicado pela linha e pela coluna do seu quadrado (a partir de 1)
+pub(crate) fn preplaced(
+    table: &Table,
+    pieces: &Pieces,
+    mut inventory: Inventory,
+) -> Result<Inventory, SolveError> {
+    let mut cells = array::new::<usize>(pieces.len());
+    for (x, line) in table.iter().enumerate() {
+        for (y, &c) in line.iter().enumerate() {
+            if c == ' ' || c == '~' {
+                continue;
+            }
+            let p_idx = pieces.iter().position(|p| p[0].ident == c).ok_or_else(|| {
+                SolveError::InvalidBoard(format!(
+                    "linha {}, coluna {}: peça {c} não existe",
+                    x + 1,
+                    y + 1
+                ))
+            })?;
+            cells[p_idx] += 1;
+        }
+    }
+    for (p_idx, &cells) in cells.iter().enumerate() {
+        let p = &pieces[p_idx][0];
+        if cells % p.size() != 0 {
+            return Err(SolveError::InvalidBoard(format!(
+                "a peça {} tem {} quadrados, mas há {cells} quadrados com {} no tabuleiro",
+                p.ident,
+                p.size(),
+                p.ident
+            )));
+        }
+        let copies = cells / p.size();
+        if copies > inventory.counts[p_idx] {
+            return Err(SolveError::InvalidBoard(format!(
+                "há {copies} cópias da peça {} no tabuleiro, mas o inventário tem {}",
+                p.ident, inventory.counts[p_idx]
+            )));
+        }
+        inventory.counts[p_idx] -= copies;
+    }
+    Ok(inventory)
+}
+
+/// Converte uma colocação no tabuleiro transposto (com as peças transpostas) para o tabuleiro
+/// original, com dimensões `dim` e a topologia dada: o quadrado (0, 0) de cada transformação é o
+/// seu primeiro quadrado, linha a linha
+fn untranspose(
+    p: Placement,
+    transposed: &Pieces,
+    dim: (i32, i32),
+    topology: Topology,
+) -> Placement {
+    let t = &transposed[p.p_idx][p.t_idx];
+    let (x, y) = p.pos;
+    let mut pos = (y, x);
+    for &(dx, dy) in t.pt.iter() {
+        pos = pos.min((y + dy, x + dx));
+    }
+    let pos = topology.wrap(pos, dim).unwrap();
+    Placement { pos, ..p }
+}
+
+/// Checa se as peças que dão a volta no tabuleiro são menores que ele: uma peça que ocupa todas
+/// as linhas (ou colunas) ligadas poderia se sobrepor a ela mesma, ou ocupar os mesmos
+/// quadrados em mais de uma posição. Na grade triangular as dimensões ligadas devem ser pares,
+/// para que os triângulos para cima e para baixo continuem alternando ao dar a volta
+pub(crate) fn check_wrap(
+    table: &Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    topology: Topology,
+) -> Result<(), SolveError> {
+    let (lin, col) = table::dim(table);
+    if topology.grid == Grid::Triangle
+        && ((topology.wrap_lines && lin % 2 != 0) || (topology.wrap_columns && col % 2 != 0))
+    {
+        return Err(SolveError::InvalidBoard(format!(
+            "as bordas ligadas do tabuleiro triangular {lin}x{col} devem ter tamanho par"
+        )));
+    }
+    for (p, _) in pieces
+        .iter()
+        .zip(inventory.counts.iter())
+        .filter(|(_, &c)| c > 0)
+    {
+        for t in p.iter() {
+            let (l, c) = t.extent();
+            if (topology.wrap_lines && l >= lin) || (topology.wrap_columns && c >= col) {
+                return Err(SolveError::InvalidBoard(format!(
+                    "a peça {} não cabe sem se sobrepor ao dar a volta no tabuleiro {lin}x{col}",
+                    t.ident
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve o tabuleiro, chamando `found` para cada solução.
+/// O tipo `T` acumula o que `found` quiser guardar (ex.: o número de soluções): cada thread tem
+/// o seu e são todos devolvidos no final, junto com as estatísticas da busca. A busca termina
+/// quando `found` devolve false.
+pub fn search<T: Default + Send>(
+    table: &Table,
+    opts: &Options,
+    found: &(dyn Fn(&Solution, &mut T) -> bool + Sync),
+) -> Result<(Box<[T]>, Stats), SolveError> {
+    run(table, opts, None, None, found)
+}
+
+/// Como [search], mas passando cada passo da busca para `tracer` (ver [crate::trace]). A busca é
+/// sempre o backtracking com uma thread, qualquer que seja o motor, e o tabuleiro não é
+/// transposto, para que as posições dos passos sejam as do tabuleiro dado
+pub fn trace<T: Default + Send>(
+    table: &Table,
+    opts: &Options,
+    tracer: &mut dyn Tracer,
+    found: &(dyn Fn(&Solution, &mut T) -> bool + Sync),
+) -> Result<(Box<[T]>, Stats), SolveError> {
+    run(table, opts, None, Some(tracer), found)
+}
+
+/// Como [search] (ou [trace], se houver `tracer`), mas com os limites de `control`: a busca pode
+/// continuar de um checkpoint (que deve ser do mesmo tabuleiro, com as mesmas peças e opções) e,
+/// se parar antes do fim, guarda o checkpoint em `control`. A busca é sempre o backtracking com
+/// uma thread, qualquer que seja o motor
+pub fn controlled<T: Default + Send>(
+    table: &Table,
+    opts: &Options,
+    control: &mut Control,
+    tracer: Option<&mut dyn Tracer>,
+    found: &(dyn Fn(&Solution, &mut T) -> bool + Sync),
+) -> Result<(Box<[T]>, Stats), SolveError> {
+    run(table, opts, Some(control), tracer, found)
+}
+
+/// Identificadores das peças, guardados no checkpoint
+fn idents(pieces: &Pieces) -> String {
+    pieces.iter().map(|p| p[0].ident).collect()
+}
+
+/// Checa se o checkpoint pode continuar a busca no tabuleiro com as peças, o inventário e as
+/// opções dadas (`break_symmetry` indica se a simetria é quebrada nesta busca)
+fn check_checkpoint(
+    checkpoint: &Checkpoint,
+    table: &Table,
+    search_table: &Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    (strategy, break_symmetry): (Strategy, bool),
+) -> Result<(), SolveError> {
+    let err = |msg: &str| Err(SolveError::InvalidBoard(format!("checkpoint {msg}")));
+    if checkpoint.board != *table || checkpoint.pieces != idents(pieces) {
+        return err("de outro tabuleiro ou de outras peças");
+    }
+    if checkpoint.inventory != inventory.counts || checkpoint.any == inventory.exact {
+        return err("de outro inventário (--inventory ou --any)");
+    }
+    if checkpoint.strategy != strategy || checkpoint.break_symmetry != break_symmetry {
+        return err("de outras opções (--strategy ou --break-symmetry)");
+    }
+    if table::dim(&checkpoint.table) != table::dim(search_table)
+        || checkpoint.mark.len() != search_table.len()
+        || checkpoint
+            .mark
+            .iter()
+            .any(|line| line.len() != search_table[0].len())
+        || checkpoint.left.len() != pieces.len()
+    {
+        return err("com dimensões inválidas");
+    }
+    let valid = |p: &Placement| p.p_idx < pieces.len() && p.t_idx < pieces[p.p_idx].len();
+    if checkpoint
+        .stack
+        .iter()
+        .any(|s| !valid(&s.placed) || s.order.iter().any(|&p_idx| p_idx >= pieces.len()))
+    {
+        return err("com uma peça inválida na pilha");
+    }
+    Ok(())
+}
+
+/// Implementação de [search], [trace] e [controlled]
+fn run<T: Default + Send>(
+    table: &Table,
+    opts: &Options,
+    mut control: Option<&mut Control>,
+    tracer: Option<&mut dyn Tracer>,
+    found: &(dyn Fn(&Solution, &mut T) -> bool + Sync),
+) -> Result<(Box<[T]>, Stats), SolveError> {
+    validate(table)?;
+    let inventory = match &opts.inventory {
+        Some(inventory) => inventory.clone(),
+        None => Inventory::all(&opts.pieces, true),
+    };
+    let inventory = &preplaced(table, &opts.pieces, inventory)?;
+    check_wrap(table, &opts.pieces, inventory, opts.topology)?;
+    let cells = table::empty_spaces(table);
+    if !inventory.fits(&opts.pieces, cells) {
+        return Err(SolveError::WrongCellCount {
+            cells,
+            area: inventory.area(&opts.pieces),
+        });
+    }
+
+    // Quebra de simetria: uma peça fica restrita a uma colocação de cada classe de colocações
+    // equivalentes, calculadas no tabuleiro original
+    let no_constraints = opts.constraints.as_ref().is_none_or(Constraints::is_empty);
+    let group = symmetry::group(table, &opts.pieces, opts.topology);
+    let breaking = if opts.break_symmetry
+        && no_constraints
+        && !opts.topology.wraps()
+        && opts.topology.grid == Grid::Square
+    {
+        symmetry::break_symmetry(
+            table,
+            &opts.pieces,
+            inventory,
+            &Constraints::none(&opts.pieces),
+            &group,
+        )
+    } else {
+        None
+    };
+    let user_constraints = match &breaking {
+        Some(breaking) => {
+            let mut constraints = Constraints::none(&opts.pieces);
+            constraints.allow_only(breaking.p_idx, &breaking.placements, &opts.pieces, table);
+            Some(constraints)
+        }
+        None => opts.constraints.clone(),
+    };
+
+    // PERFORMANCE: transpor o tabuleiro para que col <= lin (na grade triangular a transposição
+    // não preserva os vizinhos)
+    let transposed = table::dim(table).0 < table::dim(table).1
+        && opts.topology.grid != Grid::Triangle
+        && tracer.is_none();
+    let dim = table::dim(table);
+    let original = table;
+    let (mut table, pieces, topology) = if transposed {
+        (
+            table::transpost(table),
+            generator::transposed(&opts.pieces),
+            opts.topology.transposed(),
+        )
+    } else {
+        (table.clone(), opts.pieces.clone(), opts.topology)
+    };
+    let constraints = match &user_constraints {
+        Some(constraints) if transposed => constraints.transposed(&opts.pieces),
+        Some(constraints) => constraints.clone(),
+        None => Constraints::none(&pieces),
+    };
+    let constraints = &constraints;
+    if let Some(checkpoint) = control.as_ref().and_then(|c| c.resume.as_ref()) {
+        check_checkpoint(
+            checkpoint,
+            original,
+            &table,
+            &pieces,
+            inventory,
+            (opts.strategy, breaking.is_some()),
+        )?;
+    }
+    let report = |solution: &Table, placements: &[Placement], acc: &mut T| {
+        let solution = if transposed {
+            let mut original = array::new::<Placement>(placements.len());
+            for (i, &p) in placements.iter().enumerate() {
+                original[i] = untranspose(p, &pieces, dim, opts.topology);
+            }
+            Solution {
+                table: table::transpost(solution),
+                placements: original,
+                class: None,
+            }
+        } else {
+            Solution {
+                table: solution.clone(),
+                placements: placements.into(),
+                class: None,
+            }
+        };
+        let solution = match &breaking {
+            Some(breaking) if !breaking.accepts(&solution.table, &solution.placements) => {
+                // Outra solução equivalente a esta é a que representa a classe
+                return true;
+            }
+            Some(_) => Solution {
+                class: Some(symmetry::orbit_size(&solution.table, &group)),
+                ..solution
+            },
+            None => solution,
+        };
+        found(&solution, acc)
+    };
+
+    let mut results = array::new::<T>(1);
+    let nodes = match (opts.engine, tracer) {
+        (_, Some(tracer)) => backtrack::search(
+            &mut table,
+            &pieces,
+            inventory,
+            constraints,
+            topology,
+            opts.strategy,
+            tracer,
+            control.as_deref_mut(),
+            |s, p| report(s, p, &mut results[0]),
+        ),
+        (_, None) if control.is_some() => backtrack::search(
+            &mut table,
+            &pieces,
+            inventory,
+            constraints,
+            topology,
+            opts.strategy,
+            &mut NoTrace,
+            control.as_deref_mut(),
+            |s, p| report(s, p, &mut results[0]),
+        ),
+        (Engine::Backtrack, None) if opts.threads > 1 => {
+            let nodes;
+            (results, nodes) = backtrack::parallel_search(
+                &table,
+                &pieces,
+                inventory,
+                constraints,
+                topology,
+                opts.strategy,
+                opts.threads,
+                &report,
+            );
+            nodes
+        }
+        (Engine::Bitboard, None) if bitboard::supports(&table) && topology.grid == Grid::Square => {
+            bitboard::search(
+                &mut table,
+                &pieces,
+                inventory,
+                constraints,
+                topology,
+                |s, p| report(s, p, &mut results[0]),
+            )
+        }
+        (Engine::Backtrack | Engine::Bitboard, None) => backtrack::search(
+            &mut table,
+            &pieces,
+            inventory,
+            constraints,
+            topology,
+            opts.strategy,
+            &mut NoTrace,
+            None,
+            |s, p| report(s, p, &mut results[0]),
+        ),
+        (Engine::Dlx, None) => dlx::search(
+            &mut table,
+            &pieces,
+            inventory,
+            constraints,
+            topology,
+            |s, p| report(s, p, &mut results[0]),
+        ),
+    };
+    if let Some(checkpoint) = control.and_then(|c| c.checkpoint.as_mut()) {
+        checkpoint.board = original.clone();
+        checkpoint.pieces = idents(&pieces);
+        checkpoint.inventory = inventory.counts.clone();
+        checkpoint.any = !inventory.exact;
+        checkpoint.strategy = opts.strategy;
+        checkpoint.break_symmetry = breaking.is_some();
+    }
+    Ok((results, Stats { nodes }))
+}
+
+/// Devolve a primeira solução encontrada
+pub fn solve(table: &Table, opts: &Options) -> Result<Solution, SolveError> {
+    let (results, _) = search(table, opts, &|solution, first: &mut Option<Solution>| {
+        *first = Some(solution.clone());
+        false
+    })?;
+    results
+        .iter()
+        .find_map(|r| r.clone())
+        .ok_or(SolveError::NoTiling)
+}
+
+/// Devolve até `limit` soluções distintas, isto é, que não são rotações ou reflexões umas das
+/// outras (com restrições, quaisquer soluções diferentes), parando a busca ao encontrá-las.
+/// Ex.: com `limit` = 2, o tabuleiro tem solução única se e só se for devolvida uma solução
+pub fn distinct(
+    table: &Table,
+    opts: &Options,
+    limit: usize,
+) -> Result<(Box<[Solution]>, Stats), SolveError> {
+    let group = match &opts.constraints {
+        Some(constraints) if !constraints.is_empty() => Box::new([]),
+        _ => symmetry::group(table, &opts.pieces, opts.topology),
+    };
+    let found: Mutex<Pilha<Solution>> = Mutex::new(Pilha::new());
+    let (_, stats) = search(table, opts, &|solution, _: &mut ()| {
+        let mut found = found.lock().unwrap();
+        if found.size() >= limit {
+            return false;
+        }
+        if !found
+            .iter()
+            .any(|s| symmetry::equivalent(&s.table, &solution.table, &group))
+        {
+            found.push(solution.clone());
+        }
+        found.size() < limit
+    })?;
+    let found = found.into_inner().unwrap().to_array();
+    Ok((found, stats))
+}
+
+/// Devolve o número de soluções. Com a quebra de simetria, cada solução encontrada conta pelo
+/// tamanho da sua classe, então o total é o mesmo
+pub fn count(table: &Table, opts: &Options) -> Result<u64, SolveError> {
+    let (results, _) = search(table, opts, &|solution: &Solution, count: &mut u64| {
+        *count += solution.class.unwrap_or(1);
+        true
+    })?;
+    Ok(results.iter().sum())
+}
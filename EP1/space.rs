@@ -0,0 +1,201 @@
+//! Espaço 3D para o empacotamento de policubos: uma pilha de camadas, cada uma um tabuleiro
+//! (ver [Table]) com as mesmas dimensões
+
+use super::array;
+use super::pilha::Pilha;
+use super::table::{self, idx, Table};
+use std::convert::TryInto;
+
+/// space\[z\]\[x\]\[y\]: cubo (x, y) da camada z, com os mesmos caracteres do [Table]
+pub type Space = Box<[Table]>;
+
+/// Devolve as dimensões do espaço (camadas, linhas, colunas)
+pub fn dim(space: &Space) -> (i32, i32, i32) {
+    let (lin, col) = table::dim(&space[0]);
+    (space.len().try_into().unwrap(), lin, col)
+}
+
+/// Checa se (z, x, y) é uma posição válida no espaço
+pub fn ok((z, x, y): (i32, i32, i32), space: &Space) -> bool {
+    let (lay, lin, col) = dim(space);
+    0 <= z && z < lay && 0 <= x && x < lin && 0 <= y && y < col
+}
+
+/// Conta os cubos vazios do espaço
+pub fn empty_cells(space: &Space) -> usize {
+    space.iter().map(table::empty_spaces).sum()
+}
+
+/// Checa se a palavra é uma abreviação das dimensões do espaço (ex.: "3x4x5")
+fn dimensions(word: &str) -> Option<(usize, usize, usize)> {
+    let mut it = word
+        .split('x')
+        .map(|n| n.parse::<usize>().ok().filter(|&n| n > 0));
+    match (it.next(), it.next(), it.next(), it.next()) {
+        (Some(Some(lay)), Some(Some(lin)), Some(Some(col)), None) => Some((lay, lin, col)),
+        _ => None,
+    }
+}
+
+/// Lê o espaço de um texto com as camadas, de cima para baixo, no formato de [table::parse],
+/// separadas por linhas em branco. Também aceita a abreviação "3x4x5" (camadas x linhas x
+/// colunas) para um espaço vazio, com no máximo [table::MAX_CELLS] cubos
+pub fn parse(s: &str) -> Result<Space, String> {
+    if let Some((lay, lin, col)) = s.split_whitespace().next().and_then(dimensions) {
+        if s.split_whitespace().count() > 1 {
+            return Err(format!("Esperava apenas as dimensões {lay}x{lin}x{col}"));
+        }
+        if lay.saturating_mul(lin).saturating_mul(col) > table::MAX_CELLS {
+            return Err(format!(
+                "Espaço {lay}x{lin}x{col} grande demais (no máximo {} cubos)",
+                table::MAX_CELLS
+            ));
+        }
+        let mut space: Space = array::new(lay);
+        for layer in space.iter_mut() {
+            *layer = table::parse(&format!("{lin}x{col}"))?;
+        }
+        return Ok(space);
+    }
+    let mut layers: Pilha<Table> = Pilha::new();
+    let mut text = String::new();
+    // Uma linha em branco extra no final para terminar a última camada
+    for line in s.lines().chain(std::iter::once("")) {
+        if !line.trim().is_empty() {
+            text += line;
+            text.push('\n');
+            continue;
+        }
+        if text.is_empty() {
+            continue;
+        }
+        let num = layers.size() + 1;
+        let layer = table::parse(&text).map_err(|e| format!("Camada {num}: {e}"))?;
+        if !layers.empty() && table::dim(&layer) != table::dim(layers.iter().next().unwrap()) {
+            return Err(format!(
+                "Camadas 1 e {num} possuem dimensões diferentes ({:?} e {:?})!",
+                table::dim(layers.iter().next().unwrap()),
+                table::dim(&layer)
+            ));
+        }
+        layers.push(layer);
+        text.clear();
+    }
+    if layers.empty() {
+        return Err("Input vazio!".to_string());
+    }
+    Ok(layers.to_array())
+}
+
+/// Lê o espaço da entrada padrão
+pub fn scan() -> Result<Space, String> {
+    let mut s = String::new();
+    use std::io::Read;
+    std::io::stdin()
+        .read_to_string(&mut s)
+        .map_err(|e| format!("Erro de leitura: {:?}", e))?;
+    parse(&s)
+}
+
+/// Uma das 48 simetrias da caixa: a coordenada i da imagem é a coordenada perm\[i\] do cubo,
+/// com a ordem invertida se flip\[i\]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Transform {
+    pub perm: [usize; 3],
+    pub flip: [bool; 3],
+}
+
+impl Transform {
+    /// Checa se a transformação é uma rotação (e não uma reflexão)
+    pub fn is_rotation(&self) -> bool {
+        let odd_perm = (self.perm[0] > self.perm[1])
+            ^ (self.perm[0] > self.perm[2])
+            ^ (self.perm[1] > self.perm[2]);
+        !(odd_perm ^ self.flip[0] ^ self.flip[1] ^ self.flip[2])
+    }
+
+    /// Devolve a transformação inversa
+    pub fn inverse(&self) -> Transform {
+        let mut inv = Transform::default();
+        for i in 0..3 {
+            inv.perm[self.perm[i]] = i;
+            inv.flip[self.perm[i]] = self.flip[i];
+        }
+        inv
+    }
+
+    /// Aplica a transformação no espaço
+    pub fn apply(&self, space: &Space) -> Space {
+        let (lay, lin, col) = dim(space);
+        let d = [lay, lin, col];
+        let nd = [d[self.perm[0]], d[self.perm[1]], d[self.perm[2]]];
+        let mut new_space: Space = array::new(idx(nd[0]));
+        for layer in new_space.iter_mut() {
+            *layer = array::new(idx(nd[1]));
+            for line in layer.iter_mut() {
+                *line = array::new::<char>(idx(nd[2]));
+            }
+        }
+        for z in 0..lay {
+            for x in 0..lin {
+                for y in 0..col {
+                    let c = [z, x, y];
+                    let mut n = [0; 3];
+                    for i in 0..3 {
+                        n[i] = if self.flip[i] {
+                            nd[i] - 1 - c[self.perm[i]]
+                        } else {
+                            c[self.perm[i]]
+                        };
+                    }
+                    new_space[idx(n[0])][idx(n[1])][idx(n[2])] = space[idx(z)][idx(x)][idx(y)];
+                }
+            }
+        }
+        new_space
+    }
+}
+
+/// Devolve as simetrias da caixa (exceto a identidade) que levam o espaço vazio nele mesmo.
+/// Se `reflections` for falso, apenas as rotações são consideradas
+pub fn group(space: &Space, reflections: bool) -> Box<[Transform]> {
+    const PERMS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    let (lay, lin, col) = dim(space);
+    let d = [lay, lin, col];
+    let mut group = Pilha::new();
+    for perm in PERMS {
+        for k in 0..8 {
+            let t = Transform {
+                perm,
+                flip: [k & 4 != 0, k & 2 != 0, k & 1 != 0],
+            };
+            if (perm == [0, 1, 2] && k == 0)
+                || (0..3).any(|i| d[perm[i]] != d[i])
+                || (!reflections && !t.is_rotation())
+            {
+                continue;
+            }
+            if t.apply(space) == *space {
+                group.push(t);
+            }
+        }
+    }
+    group.to_array()
+}
+
+/// Checa se a solução é a menor (lexicograficamente) dentre suas imagens pelas simetrias
+pub fn is_canonical(space: &Space, group: &[Transform]) -> bool {
+    group.iter().all(|t| space <= &t.apply(space))
+}
+
+/// Checa se as duas soluções são iguais a menos das simetrias
+pub fn equivalent(a: &Space, b: &Space, group: &[Transform]) -> bool {
+    a == b || group.iter().any(|t| t.apply(a) == *b)
+}
@@ -0,0 +1,406 @@
+//! Desenho das soluções em SVG, para apresentações.
+//!
+//! Cada peça é um único polígono preenchido, com contorno grosso nas bordas entre peças e linhas
+//! finas entre os quadrados da mesma peça. Os quadrados que não podem ser ocupados são
+//! hachurados, e peças vizinhas nunca têm a mesma cor. Várias soluções podem ser desenhadas
+//! lado a lado, numa galeria.
+//!
+//! Os vértices dos quadrados são calculados numa rede de inteiros de cada grade (ver [corners]),
+//! para que os vértices compartilhados por quadrados vizinhos sejam exatamente iguais.
+
+use super::array;
+use super::grid::Grid;
+use super::pilha::Pilha;
+use super::poliomino::{Pieces, Placement};
+use super::table::{self, idx, Table, Topology};
+
+/// Ponto do desenho, em pixels
+type Point = (f64, f64);
+
+/// Aresta de um quadrado, de um vértice ao seguinte no sentido horário
+type Edge = ((i32, i32), (i32, i32));
+
+/// Cores das peças, usadas em ordem; peças com muitos vizinhos usam cores geradas
+const PALETTE: [&str; 8] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+];
+
+/// Tabuleiro resolvido a desenhar: region\[x\]\[y\] é a peça que cobre o quadrado (x, y), ou
+/// None nos quadrados vazios e nos que não podem ser ocupados
+#[derive(Clone, Debug, Default)]
+pub struct Drawing {
+    table: Table,
+    region: Box<[Box<[Option<usize>]>]>,
+    regions: usize,
+    topology: Topology,
+}
+
+impl Drawing {
+    /// Separa as peças do tabuleiro: cada colocação (a transformação pieces\[p_idx\]\[t_idx\]
+    /// em pos, como nas soluções do [crate::solver]) é uma peça, e os outros quadrados com
+    /// letras são separados em grupos conexos de letras iguais (ex.: as peças já colocadas).
+    /// Sem as colocações, cópias vizinhas da mesma peça aparecem como uma só
+    pub fn new(
+        table: &Table,
+        placements: &[Placement],
+        pieces: &Pieces,
+        topology: Topology,
+    ) -> Drawing {
+        let (lin, col) = table::dim(table);
+        let mut region = array::new::<Box<[Option<usize>]>>(idx(lin));
+        for line in region.iter_mut() {
+            *line = array::new(idx(col));
+        }
+        let mut regions = 0;
+        for p in placements.iter() {
+            let t = &pieces[p.p_idx][p.t_idx];
+            let (x, y) = p.pos;
+            for (dx, dy) in std::iter::once((0, 0)).chain(t.pt.iter().copied()) {
+                let (cx, cy) = topology.wrap((x + dx, y + dy), (lin, col)).unwrap();
+                region[idx(cx)][idx(cy)] = Some(regions);
+            }
+            regions += 1;
+        }
+        for x in 0..lin {
+            for y in 0..col {
+                let c = table[idx(x)][idx(y)];
+                if c == ' ' || c == '~' || region[idx(x)][idx(y)].is_some() {
+                    continue;
+                }
+                let mut stack = Pilha::new();
+                stack.push((x, y));
+                region[idx(x)][idx(y)] = Some(regions);
+                while !stack.empty() {
+                    let (cx, cy) = *stack.back();
+                    stack.pop();
+                    for &(dx, dy) in topology.grid.neighbours((cx, cy)) {
+                        let Some((px, py)) = topology.wrap((cx + dx, cy + dy), (lin, col)) else {
+                            continue;
+                        };
+                        if table[idx(px)][idx(py)] == c && region[idx(px)][idx(py)].is_none() {
+                            region[idx(px)][idx(py)] = Some(regions);
+                            stack.push((px, py));
+                        }
+                    }
+                }
+                regions += 1;
+            }
+        }
+        Drawing {
+            table: table.clone(),
+            region,
+            regions,
+            topology,
+        }
+    }
+
+    /// Quadrados do tabuleiro
+    fn cells(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (lin, col) = table::dim(&self.table);
+        (0..lin).flat_map(move |x| (0..col).map(move |y| (x, y)))
+    }
+
+    /// Cor de cada peça, com peças vizinhas sempre de cores diferentes: cada peça (na ordem
+    /// do seu primeiro quadrado) recebe a primeira cor que nenhum vizinho já colorido usa
+    fn colors(&self) -> Box<[usize]> {
+        let (lin, col) = table::dim(&self.table);
+        let mut neighbours = array::new::<Pilha<usize>>(self.regions);
+        for (x, y) in self.cells() {
+            let Some(r) = self.region[idx(x)][idx(y)] else {
+                continue;
+            };
+            for &(dx, dy) in self.topology.grid.neighbours((x, y)) {
+                let Some((px, py)) = self.topology.wrap((x + dx, y + dy), (lin, col)) else {
+                    continue;
+                };
+                if let Some(s) = self.region[idx(px)][idx(py)] {
+                    if s != r {
+                        neighbours[r].push(s);
+                    }
+                }
+            }
+        }
+        let mut colors = array::new::<Option<usize>>(self.regions);
+        for r in 0..self.regions {
+            let used = |c: usize| neighbours[r].iter().any(|&s| colors[s] == Some(c));
+            colors[r] = (0..).find(|&c| !used(c));
+        }
+        colors.iter().map(|c| c.unwrap()).collect()
+    }
+}
+
+/// Aparência do desenho
+#[derive(Clone, Copy, Debug)]
+pub struct Style {
+    /// Largura de um quadrado, em pixels
+    pub cell: f64,
+    /// Escreve a letra de cada peça
+    pub labels: bool,
+    /// Número de soluções por linha da galeria
+    pub columns: usize,
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            cell: 40.0,
+            labels: false,
+            columns: 4,
+        }
+    }
+}
+
+/// Vértices do quadrado (x, y) no sentido horário (com o eixo vertical para baixo), na rede de
+/// inteiros da grade:
+/// - quadrada: os cantos do quadrado unitário da linha x e coluna y;
+/// - hexagonal: hexágono com a ponta para cima e centro (2y + x, 3x), em unidades de meia
+///   largura na horizontal e de um quarto da altura na vertical;
+/// - triangular: triângulo com a base de 2 unidades começando em y, entre as alturas x e x + 1
+fn corners(grid: Grid, (x, y): (i32, i32)) -> Box<[(i32, i32)]> {
+    match grid {
+        Grid::Square => Box::new([(y, x), (y + 1, x), (y + 1, x + 1), (y, x + 1)]),
+        Grid::Hex => {
+            let (u, v) = (2 * y + x, 3 * x);
+            Box::new([
+                (u, v - 2),
+                (u + 1, v - 1),
+                (u + 1, v + 1),
+                (u, v + 2),
+                (u - 1, v + 1),
+                (u - 1, v - 1),
+            ])
+        }
+        Grid::Triangle if grid.parity((x, y)) => Box::new([(y, x), (y + 2, x), (y + 1, x + 1)]),
+        Grid::Triangle => Box::new([(y + 1, x), (y + 2, x + 1), (y, x + 1)]),
+    }
+}
+
+/// Tamanho em pixels de uma unidade da rede de [corners] em cada eixo, para quadrados de
+/// largura `cell`
+fn scale(grid: Grid, cell: f64) -> (f64, f64) {
+    let sqrt3 = 3f64.sqrt();
+    match grid {
+        Grid::Square => (cell, cell),
+        Grid::Hex => (cell / 2.0, cell / 2.0 / sqrt3),
+        Grid::Triangle => (cell / 2.0, cell * sqrt3 / 2.0),
+    }
+}
+
+/// Caminho SVG fechado pelos vértices dados
+fn polygon(points: impl Iterator<Item = Point>) -> String {
+    let mut d = String::new();
+    for (i, (px, py)) in points.enumerate() {
+        d += &format!("{}{px:.1} {py:.1} ", if i == 0 { "M" } else { "L" });
+    }
+    d + "Z"
+}
+
+/// Texto com os caracteres especiais do XML escapados
+fn text(s: &str) -> String {
+    let mut result = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => result += "&amp;",
+            '<' => result += "&lt;",
+            '>' => result += "&gt;",
+            '"' => result += "&quot;",
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Cor da peça
+fn color(c: usize) -> String {
+    match PALETTE.get(c) {
+        Some(color) => color.to_string(),
+        None => format!("hsl({:.0}, 60%, 75%)", (c as f64 * 137.5) % 360.0),
+    }
+}
+
+/// Desenha as soluções numa galeria de `style.columns` soluções por linha (uma só solução é
+/// desenhada sozinha) e devolve o texto do SVG
+pub fn render(drawings: &[Drawing], style: &Style) -> String {
+    // Caixa de cada desenho na rede de cada grade
+    let boxes: Box<[(Point, Point)]> = drawings
+        .iter()
+        .map(|d| {
+            let (sx, sy) = scale(d.topology.grid, style.cell);
+            let (mut min, mut max) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+            for cell in d.cells() {
+                for &(u, v) in corners(d.topology.grid, cell).iter() {
+                    let (px, py) = (u as f64 * sx, v as f64 * sy);
+                    min = (min.0.min(px), min.1.min(py));
+                    max = (max.0.max(px), max.1.max(py));
+                }
+            }
+            (min, max)
+        })
+        .collect();
+    let width = boxes.iter().map(|(a, b)| b.0 - a.0).fold(0.0, f64::max);
+    let height = boxes.iter().map(|(a, b)| b.1 - a.1).fold(0.0, f64::max);
+    let margin = style.cell / 2.0;
+    let columns = style.columns.clamp(1, drawings.len().max(1));
+    let rows = drawings.len().div_ceil(columns);
+    let total = (
+        columns as f64 * (width + margin) + margin,
+        rows as f64 * (height + margin) + margin,
+    );
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" \
+         viewBox=\"0 0 {:.1} {:.1}\">\n",
+        total.0.ceil(),
+        total.1.ceil(),
+        total.0,
+        total.1
+    );
+    let hatch = style.cell / 6.0;
+    svg += &format!(
+        "<defs><pattern id=\"hatch\" width=\"{hatch:.1}\" height=\"{hatch:.1}\" \
+         patternUnits=\"userSpaceOnUse\" patternTransform=\"rotate(45)\">\
+         <line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"{hatch:.1}\" stroke=\"#888\" \
+         stroke-width=\"{:.1}\"/></pattern></defs>\n",
+        hatch / 3.0
+    );
+    for (i, (d, (min, _))) in drawings.iter().zip(boxes.iter()).enumerate() {
+        let offset = (
+            margin + (i % columns) as f64 * (width + margin) - min.0,
+            margin + (i / columns) as f64 * (height + margin) - min.1,
+        );
+        svg += &format!(
+            "<g transform=\"translate({:.1} {:.1})\">\n",
+            offset.0, offset.1
+        );
+        svg += &draw(d, style);
+        svg += "</g>\n";
+    }
+    svg + "</svg>\n"
+}
+
+/// Elementos SVG de uma solução
+fn draw(d: &Drawing, style: &Style) -> String {
+    let grid = d.topology.grid;
+    let (sx, sy) = scale(grid, style.cell);
+    let point = |(u, v): (i32, i32)| (u as f64 * sx, v as f64 * sy);
+    let thin = style.cell / 40.0;
+    let thick = style.cell / 12.0;
+    let mut svg = String::new();
+    // Quadrados vazios e que não podem ser ocupados
+    for cell in d.cells() {
+        let fill = match d.table[idx(cell.0)][idx(cell.1)] {
+            ' ' => "white",
+            '~' => "url(#hatch)",
+            _ => continue,
+        };
+        svg += &format!(
+            "<path d=\"{}\" fill=\"{fill}\" stroke=\"#888\" stroke-width=\"{thin:.1}\"/>\n",
+            polygon(corners(grid, cell).iter().map(|&c| point(c)))
+        );
+    }
+    // Arestas dos quadrados de cada peça
+    let mut edges = array::new::<Pilha<Edge>>(d.regions);
+    for cell in d.cells() {
+        if let Some(r) = d.region[idx(cell.0)][idx(cell.1)] {
+            let c = corners(grid, cell);
+            for k in 0..c.len() {
+                edges[r].push((c[k], c[(k + 1) % c.len()]));
+            }
+        }
+    }
+    let colors = d.colors();
+    for (r, edges) in edges.iter().enumerate() {
+        let mut edges = edges.iter().copied().collect::<Box<[Edge]>>();
+        edges.sort();
+        // Arestas internas aparecem nos dois sentidos
+        let inner = |&(a, b): &Edge| edges.binary_search(&(b, a)).is_ok();
+        let border: Box<[Edge]> = edges.iter().copied().filter(|e| !inner(e)).collect();
+        svg += &format!(
+            "<path d=\"{}\" fill=\"{}\" fill-rule=\"evenodd\" stroke=\"black\" \
+             stroke-width=\"{thick:.1}\" stroke-linejoin=\"round\"/>\n",
+            outline(&border, point),
+            color(colors[r])
+        );
+        for &(a, b) in edges.iter().filter(|&&(a, b)| a < b && inner(&(a, b))) {
+            let ((x1, y1), (x2, y2)) = (point(a), point(b));
+            svg += &format!(
+                "<line x1=\"{x1:.1}\" y1=\"{y1:.1}\" x2=\"{x2:.1}\" y2=\"{y2:.1}\" \
+                 stroke=\"#555\" stroke-width=\"{thin:.1}\"/>\n"
+            );
+        }
+    }
+    if style.labels {
+        svg += &labels(d, style, point);
+    }
+    svg
+}
+
+/// Contorno da peça: encadeia as arestas da borda (ordenadas) em caminhos fechados, cada um
+/// seguindo, a partir do fim de uma aresta, alguma aresta ainda não usada que começa nele
+fn outline(border: &[Edge], point: impl Fn((i32, i32)) -> Point) -> String {
+    let mut used = array::new::<bool>(border.len());
+    let mut d = String::new();
+    for start in 0..border.len() {
+        if used[start] {
+            continue;
+        }
+        let mut points: Pilha<Point> = Pilha::new();
+        let mut e = start;
+        loop {
+            used[e] = true;
+            points.push(point(border[e].0));
+            let end = border[e].1;
+            let from = border.partition_point(|&(a, _)| a < end);
+            match (from..border.len()).find(|&k| border[k].0 == end && !used[k]) {
+                Some(k) => e = k,
+                None => break,
+            }
+        }
+        if !d.is_empty() {
+            d.push(' ');
+        }
+        d += &polygon(points.iter().copied());
+    }
+    d
+}
+
+/// Letra de cada peça, no quadrado da peça mais próximo do centro dela
+fn labels(d: &Drawing, style: &Style, point: impl Fn((i32, i32)) -> Point) -> String {
+    let grid = d.topology.grid;
+    let center = |cell: (i32, i32)| {
+        let c = corners(grid, cell);
+        let (sx, sy) = c.iter().fold((0.0, 0.0), |(sx, sy), &v| {
+            let (px, py) = point(v);
+            (sx + px, sy + py)
+        });
+        (sx / c.len() as f64, sy / c.len() as f64)
+    };
+    let mut sums = array::new::<(f64, f64, usize)>(d.regions);
+    for cell in d.cells() {
+        if let Some(r) = d.region[idx(cell.0)][idx(cell.1)] {
+            let (px, py) = center(cell);
+            sums[r] = (sums[r].0 + px, sums[r].1 + py, sums[r].2 + 1);
+        }
+    }
+    let mut best = array::new::<Option<((i32, i32), f64)>>(d.regions);
+    for cell in d.cells() {
+        if let Some(r) = d.region[idx(cell.0)][idx(cell.1)] {
+            let (px, py) = center(cell);
+            let (mx, my) = (sums[r].0 / sums[r].2 as f64, sums[r].1 / sums[r].2 as f64);
+            let dist = (px - mx).powi(2) + (py - my).powi(2);
+            if best[r].is_none_or(|(_, b)| dist < b) {
+                best[r] = Some((cell, dist));
+            }
+        }
+    }
+    let mut svg = String::new();
+    for &(cell, _) in best.iter().flatten() {
+        let (px, py) = center(cell);
+        svg += &format!(
+            "<text x=\"{px:.1}\" y=\"{py:.1}\" font-family=\"sans-serif\" \
+             font-size=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+            style.cell * 0.45,
+            text(&d.table[idx(cell.0)][idx(cell.1)].to_string())
+        );
+    }
+    svg
+}
@@ -0,0 +1,287 @@
+//! Simetrias do tabuleiro, usadas para contar soluções a menos de rotações e reflexões
+
+use super::array;
+use super::constraints::Constraints;
+use super::grid::Grid;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::generator;
+use super::poliomino::{Pieces, Placement};
+use super::table::{dim, idx, ok, Table, Topology};
+
+/// Uma simetria da grade: gira `rotations` vezes em torno da origem (ver `Geometry::rotate`),
+/// depois reflete (se `reflect`, ver `Geometry::reflect`) e por fim translada por `shift`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Transform {
+    pub grid: Grid,
+    pub rotations: usize,
+    pub reflect: bool,
+    pub shift: (i32, i32),
+}
+
+impl Transform {
+    /// Checa se a transformação é uma rotação (e não uma reflexão)
+    pub fn is_rotation(&self) -> bool {
+        !self.reflect
+    }
+
+    /// Devolve a posição do quadrado (x, y) depois da transformação
+    pub fn map(&self, cell: (i32, i32)) -> (i32, i32) {
+        let geometry = self.grid.geometry();
+        let mut cell = cell;
+        for _ in 0..self.rotations {
+            cell = geometry.rotate(cell);
+        }
+        if self.reflect {
+            cell = geometry.reflect(cell);
+        }
+        (cell.0 + self.shift.0, cell.1 + self.shift.1)
+    }
+
+    /// Aplica a transformação na colocação de uma peça, encontrando a transformação da peça com
+    /// a forma resultante (`None` se a peça não tem essa forma, como a reflexão de uma peça de
+    /// um lado só)
+    fn map_placement(&self, placement: &Placement, pieces: &Pieces) -> Option<Placement> {
+        let Placement { p_idx, t_idx, pos } = *placement;
+        let p = &pieces[p_idx][t_idx];
+        let mut cells = array::new::<(i32, i32)>(p.size());
+        cells[0] = self.map(pos);
+        for (k, &(dx, dy)) in p.pt.iter().enumerate() {
+            cells[k + 1] = self.map((pos.0 + dx, pos.1 + dy));
+        }
+        let anchor = *cells.iter().min().unwrap();
+        let down = self.grid.geometry().parity(anchor);
+        let t_idx = pieces[p_idx].iter().position(|t| {
+            t.down == down
+                && t.pt
+                    .iter()
+                    .all(|&(dx, dy)| cells.contains(&(anchor.0 + dx, anchor.1 + dy)))
+        })?;
+        Some(Placement {
+            p_idx,
+            t_idx,
+            pos: anchor,
+        })
+    }
+
+    /// Aplica a transformação no tabuleiro, que deve levar os quadrados que não estão
+    /// bloqueados neles mesmos (como as de [board_group]); os bloqueados não mudam
+    pub fn apply(&self, table: &Table) -> Table {
+        let mut new_table = table.clone();
+        for (x, line) in table.iter().enumerate() {
+            for (y, &c) in line.iter().enumerate() {
+                if c != '~' {
+                    let (nx, ny) = self.map((x as i32, y as i32));
+                    new_table[idx(nx)][idx(ny)] = c;
+                }
+            }
+        }
+        new_table
+    }
+
+    /// Checa se a transformação leva cada quadrado não bloqueado do tabuleiro num quadrado com
+    /// o mesmo conteúdo e quadrados vizinhos (pela topologia, que pode ligar as bordas) em
+    /// quadrados vizinhos
+    fn preserves(&self, table: &Table, topology: Topology) -> bool {
+        let (lin, col) = dim(table);
+        let geometry = topology.grid.geometry();
+        let free = |(x, y): (i32, i32)| ok(x, y, table) && table[idx(x)][idx(y)] != '~';
+        (0..lin).all(|x| {
+            (0..col).all(|y| {
+                if !free((x, y)) {
+                    return true;
+                }
+                let a = self.map((x, y));
+                if !free(a) || table[idx(a.0)][idx(a.1)] != table[idx(x)][idx(y)] {
+                    return false;
+                }
+                geometry.neighbours((x, y)).iter().all(|&(dx, dy)| {
+                    let Some(n) = topology.wrap((x + dx, y + dy), (lin, col)) else {
+                        return true;
+                    };
+                    if !free(n) {
+                        return true;
+                    }
+                    let b = self.map(n);
+                    geometry
+                        .neighbours(a)
+                        .iter()
+                        .any(|&(ex, ey)| topology.wrap((a.0 + ex, a.1 + ey), (lin, col)) == Some(b))
+                })
+            })
+        })
+    }
+}
+
+/// Quadrados do tabuleiro que não estão bloqueados, em ordem
+fn free_cells(table: &Table) -> impl Iterator<Item = (i32, i32)> + '_ {
+    let (lin, col) = dim(table);
+    (0..lin)
+        .flat_map(move |x| (0..col).map(move |y| (x, y)))
+        .filter(move |&(x, y)| table[idx(x)][idx(y)] != '~')
+}
+
+/// Devolve as simetrias (exceto a identidade) que levam o tabuleiro vazio nele mesmo, isto é,
+/// que levam os quadrados que não estão bloqueados (vazios ou com peças já colocadas) em
+/// quadrados com o mesmo conteúdo, preservando os vizinhos.
+/// Cada rotação ou reflexão da grade é transladada para que o menor quadrado não bloqueado da
+/// imagem seja o do tabuleiro, então as simetrias não dependem do retângulo em volta da
+/// região (ex.: um paralelogramo na grade triangular é simétrico por um giro de 180°). Na grade
+/// triangular a translação deve levar triângulos para cima em triângulos para cima.
+/// Se `reflections` for falso, apenas as rotações são consideradas (peças de um lado só)
+pub fn board_group(table: &Table, reflections: bool, topology: Topology) -> Box<[Transform]> {
+    let geometry = topology.grid.geometry();
+    let mut group = Pilha::new();
+    let Some(first) = free_cells(table).next() else {
+        return Box::new([]);
+    };
+    for rotations in 0..geometry.rotations() {
+        for reflect in [false, true] {
+            if (rotations == 0 && !reflect) || (reflect && !reflections) {
+                continue;
+            }
+            let mut t = Transform {
+                grid: topology.grid,
+                rotations,
+                reflect,
+                shift: (0, 0),
+            };
+            let least = free_cells(table).map(|cell| t.map(cell)).min().unwrap();
+            t.shift = (first.0 - least.0, first.1 - least.1);
+            if !geometry.parity(t.shift) && t.preserves(table, topology) {
+                group.push(t);
+            }
+        }
+    }
+    let mut arr = array::new(group.size());
+    for (i, t) in group.iter().enumerate() {
+        arr[i] = *t;
+    }
+    arr
+}
+
+/// Devolve as simetrias do tabuleiro (ver [board_group]) que levam as peças em peças e preservam
+/// as bordas ligadas pela topologia: as reflexões só são consideradas se a imagem no espelho de
+/// cada peça é uma das suas transformações, e as que transpõem o tabuleiro só se as linhas e as
+/// colunas dão a volta igualmente
+pub fn group(table: &Table, pieces: &Pieces, topology: Topology) -> Box<[Transform]> {
+    let reflections = generator::reflective(pieces, topology.grid);
+    board_group(table, reflections, topology)
+}
+
+/// Checa se a solução é a menor (lexicograficamente) dentre suas imagens pelas simetrias do
+/// tabuleiro, de modo que exatamente uma solução de cada classe de equivalência é canônica
+pub fn is_canonical(table: &Table, group: &[Transform]) -> bool {
+    group.iter().all(|t| table <= &t.apply(table))
+}
+
+/// Checa se as duas soluções são iguais a menos das simetrias do tabuleiro
+pub fn equivalent(a: &Table, b: &Table, group: &[Transform]) -> bool {
+    a == b || group.iter().any(|t| t.apply(a) == *b)
+}
+
+/// Devolve o número de soluções equivalentes à solução pelas simetrias do tabuleiro (incluindo
+/// ela mesma)
+pub fn orbit_size(table: &Table, group: &[Transform]) -> u64 {
+    let fixed = group.iter().filter(|t| t.apply(table) == *table).count();
+    ((group.len() + 1) / (fixed + 1)) as u64
+}
+
+/// Quebra de simetria: a peça p_idx, que aparece exatamente uma vez em toda solução, só pode ser
+/// colocada em uma colocação de cada órbita pelas simetrias do tabuleiro. Assim cada classe de
+/// soluções equivalentes é encontrada uma única vez, exceto quando a colocação escolhida é
+/// fixada por uma simetria, e então as soluções são filtradas por [Breaking::accepts]
+pub struct Breaking {
+    pub p_idx: usize,
+    /// Colocações permitidas da peça (uma por órbita)
+    pub placements: Box<[Placement]>,
+    /// Colocações permitidas fixadas por alguma simetria além da identidade, com essas
+    /// simetrias
+    stabilized: Box<[(Placement, Box<[Transform]>)]>,
+}
+
+impl Breaking {
+    /// Checa se a solução é a que representa a sua classe: se a peça está numa colocação fixada
+    /// por simetrias, a solução deve ser a menor (lexicograficamente) dentre suas imagens por
+    /// elas
+    pub fn accepts(&self, table: &Table, placements: &[Placement]) -> bool {
+        let Some(placed) = placements.iter().find(|q| q.p_idx == self.p_idx) else {
+            return true;
+        };
+        match self.stabilized.iter().find(|(q, _)| q == placed) {
+            Some((_, stab)) => stab.iter().all(|t| *table <= t.apply(table)),
+            None => true,
+        }
+    }
+}
+
+/// Escolhe a peça usada na quebra de simetria: dentre as peças com uma cópia que deve ser usada,
+/// a que tem menos colocações fixadas por simetrias (que precisam ser filtradas) e, depois, menos
+/// colocações. Devolve None se o tabuleiro não tem simetrias ou nenhuma peça serve.
+/// O tabuleiro deve ser comum (sem bordas ligadas)
+pub fn break_symmetry(
+    table: &Table,
+    pieces: &Pieces,
+    inventory: &Inventory,
+    constraints: &Constraints,
+    group: &[Transform],
+) -> Option<Breaking> {
+    if group.is_empty() || !inventory.exact {
+        return None;
+    }
+    let (lin, col) = dim(table);
+    let mut best: Option<Breaking> = None;
+    for (p_idx, transformations) in pieces.iter().enumerate() {
+        if inventory.counts[p_idx] != 1 {
+            continue;
+        }
+        let mut reps: Pilha<Placement> = Pilha::new();
+        let mut stabilized: Pilha<(Placement, Box<[Transform]>)> = Pilha::new();
+        for (t_idx, p) in transformations.iter().enumerate() {
+            for x in 0..lin {
+                for y in 0..col {
+                    let placement = Placement {
+                        p_idx,
+                        t_idx,
+                        pos: (x, y),
+                    };
+                    if !p.fits_in(x, y, table, Topology::PLANE)
+                        || !constraints.allows(&placement, pieces, Topology::PLANE)
+                    {
+                        continue;
+                    }
+                    // O representante da órbita é a menor das suas colocações
+                    let mut stab: Pilha<Transform> = Pilha::new();
+                    let mut smallest = true;
+                    for t in group.iter() {
+                        // O grupo deve levar as peças em peças (ver [group])
+                        let image = t.map_placement(&placement, pieces)?;
+                        smallest &= image >= placement;
+                        if image == placement {
+                            stab.push(*t);
+                        }
+                    }
+                    if !smallest {
+                        continue;
+                    }
+                    reps.push(placement);
+                    if !stab.empty() {
+                        stabilized.push((placement, stab.to_array()));
+                    }
+                }
+            }
+        }
+        let better = match &best {
+            None => true,
+            Some(b) => (stabilized.size(), reps.size()) < (b.stabilized.len(), b.placements.len()),
+        };
+        if better {
+            best = Some(Breaking {
+                p_idx,
+                placements: reps.to_array(),
+                stabilized: stabilized.to_array(),
+            });
+        }
+    }
+    best
+}
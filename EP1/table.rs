@@ -1,6 +1,7 @@
 //! Utilidades para ler e lidar com o tabuleiro
 
 use super::array;
+use super::grid::Grid;
 use std::convert::TryInto;
 
 /// '~': Quadrado não pode ser ocupado
@@ -23,6 +24,60 @@ pub fn ok(x: i32, y: i32, table: &Table) -> bool {
     0 <= x && x < dim.0 && 0 <= y && y < dim.1
 }
 
+/// Como as bordas do tabuleiro se ligam: se `wrap_lines`, a linha depois da última é a primeira
+/// (cilindro na vertical), e se `wrap_columns`, a coluna depois da última é a primeira (cilindro
+/// na horizontal). Com ambos o tabuleiro é um toro.
+/// `grid` é a geometria dos quadrados do tabuleiro (ver [Grid])
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Topology {
+    pub wrap_lines: bool,
+    pub wrap_columns: bool,
+    pub grid: Grid,
+}
+
+impl Topology {
+    /// Tabuleiro comum, em que fora das bordas não há quadrados
+    pub const PLANE: Topology = Topology {
+        wrap_lines: false,
+        wrap_columns: false,
+        grid: Grid::Square,
+    };
+
+    /// Checa se alguma borda se liga à oposta
+    pub fn wraps(&self) -> bool {
+        self.wrap_lines || self.wrap_columns
+    }
+
+    /// A mesma topologia no tabuleiro transposto
+    pub fn transposed(&self) -> Topology {
+        Topology {
+            wrap_lines: self.wrap_columns,
+            wrap_columns: self.wrap_lines,
+            grid: self.grid,
+        }
+    }
+
+    /// Devolve o quadrado do tabuleiro com dimensões `dim` em que (x, y) cai, dando a volta nas
+    /// bordas ligadas (None se (x, y) está fora do tabuleiro)
+    pub fn wrap(&self, (x, y): (i32, i32), (lin, col): (i32, i32)) -> Option<(i32, i32)> {
+        let x = if self.wrap_lines {
+            x.rem_euclid(lin)
+        } else {
+            x
+        };
+        let y = if self.wrap_columns {
+            y.rem_euclid(col)
+        } else {
+            y
+        };
+        if 0 <= x && x < lin && 0 <= y && y < col {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+}
+
 /// Devolve as dimensões do tabuleiro
 pub fn dim(table: &Table) -> (i32, i32) {
     (
@@ -44,38 +99,173 @@ pub fn next_empty(mut x: i32, mut y: i32, table: &Table) -> (i32, i32) {
     (x, y)
 }
 
-/// Lê o tabuleiro
-pub fn scan() -> Table {
+/// Conta os espaços vazios do tabuleiro
+pub fn empty_spaces(table: &Table) -> usize {
+    table.iter().fold(0, |count, line| {
+        count
+            + line
+                .iter()
+                .fold(0, |count, &c| count + if c == ' ' { 1 } else { 0 })
+    })
+}
+
+/// Devolve o tabuleiro transposto
+pub fn transpost(table: &Table) -> Table {
+    let (lin, col) = dim(table);
+    let mut new_table = array::new(idx(col));
+    for new_lin in new_table.iter_mut() {
+        *new_lin = array::new::<char>(idx(lin));
+    }
+    for (x, line) in table.iter().enumerate() {
+        for (y, &c) in line.iter().enumerate() {
+            new_table[y][x] = c;
+        }
+    }
+    new_table
+}
+
+/// Escreve o tabuleiro no formato lido por [parse], com '0' para os quadrados vazios, '1' para
+/// os que não podem ser ocupados e as letras das peças já colocadas
+pub fn format(table: &Table) -> String {
     let mut s = String::new();
-    use std::io::Read;
-    std::io::stdin()
-        .read_to_string(&mut s)
-        .unwrap_or_else(|e| panic!("Erro de leitura: {:?}", e));
-    let lines = s.lines().count();
-    assert!(lines > 0, "Input vazio!");
+    for line in table.iter() {
+        for (y, &c) in line.iter().enumerate() {
+            if y > 0 {
+                s.push(' ');
+            }
+            s.push(match c {
+                ' ' => '0',
+                '~' => '1',
+                c => c,
+            });
+        }
+        s.push('\n');
+    }
+    s
+}
+
+/// Devolve as palavras da linha, cada uma com a coluna (a partir de 1) em que começa
+fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace().map(move |w| {
+        let offset = w.as_ptr() as usize - line.as_ptr() as usize;
+        (line[..offset].chars().count() + 1, w)
+    })
+}
+
+/// Número máximo de quadrados de um tabuleiro dado pela abreviação "LxC", para que dimensões
+/// enormes não façam o programa alocar memória sem limite
+pub const MAX_CELLS: usize = 1 << 20;
+
+/// Checa se o caractere pode identificar uma peça no tabuleiro: '0' e '.' são quadrados vazios,
+/// '1', '#' e '~' quadrados que não podem ser ocupados e espaços separam os quadrados
+pub fn is_piece(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '0' | '.' | '1' | '#' | '~')
+}
+
+/// Checa se a palavra é uma abreviação das dimensões do tabuleiro (ex.: "6x10")
+fn dimensions(word: &str) -> Option<(usize, usize)> {
+    let (lin, col) = word.split_once('x')?;
+    match (lin.parse::<usize>(), col.parse::<usize>()) {
+        (Ok(lin), Ok(col)) if lin > 0 && col > 0 => Some((lin, col)),
+        _ => None,
+    }
+}
+
+/// Lê a abreviação "LxC -holes l,c l,c ...": um tabuleiro vazio com L linhas e C colunas, a
+/// menos dos quadrados (l, c) (contados a partir de 0), que não podem ser ocupados
+fn parse_shorthand(s: &str) -> Result<Table, String> {
+    let mut tokens = s
+        .lines()
+        .enumerate()
+        .flat_map(|(num, line)| words(line).map(move |(col, w)| (num + 1, col, w)));
+    let (_, _, first) = tokens.next().unwrap();
+    let (lin, col) = dimensions(first).unwrap();
+    if lin.saturating_mul(col) > MAX_CELLS {
+        return Err(format!(
+            "Tabuleiro {lin}x{col} grande demais (no máximo {MAX_CELLS} quadrados)"
+        ));
+    }
+    let mut table: Table = array::new(lin);
+    for line in table.iter_mut() {
+        *line = array::new::<char>(col);
+        for c in line.iter_mut() {
+            *c = ' ';
+        }
+    }
+    let mut holes = false;
+    for (num, column, w) in tokens {
+        let err = |msg: &str| format!("Erro na linha {num}, coluna {column}: {msg} \"{w}\"");
+        if !holes {
+            if w != "-holes" {
+                return Err(err("esperava -holes, encontrou"));
+            }
+            holes = true;
+            continue;
+        }
+        let (x, y) = w
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.parse::<usize>().ok()?, y.parse::<usize>().ok()?)))
+            .ok_or_else(|| err("buraco inválido"))?;
+        if x >= lin || y >= col {
+            return Err(err("buraco fora do tabuleiro"));
+        }
+        table[x][y] = '~';
+    }
+    Ok(table)
+}
+
+/// Lê o tabuleiro de um texto com uma linha do tabuleiro por linha, com um caractere por
+/// quadrado (separados ou não por espaços):
+/// '0' ou '.' é um quadrado vazio, '1', '#' ou '~' (como nas soluções impressas) um quadrado
+/// que não pode ser ocupado e qualquer outro caractere (ver [is_piece]) um quadrado já ocupado
+/// pela peça com esse identificador.
+/// Também aceita a abreviação "6x10" ou "8x8 -holes 3,3 3,4 4,3 4,4" (ver [parse_shorthand]).
+/// Os erros indicam a linha e a coluna (a partir de 1): do texto na abreviação e do quadrado no
+/// tabuleiro desenhado
+pub fn parse(s: &str) -> Result<Table, String> {
+    // Linhas em branco no final são ignoradas
+    let lines = s.lines().count() - s.lines().rev().take_while(|l| l.trim().is_empty()).count();
+    if lines == 0 {
+        return Err("Input vazio!".to_string());
+    }
+    if let Some((_, first)) = s.lines().flat_map(words).next() {
+        if dimensions(first).is_some() {
+            return parse_shorthand(s);
+        }
+    }
     let mut table: Table = array::new(lines);
-    let mut columns: Option<usize> = None;
-    for (num, line) in s.lines().enumerate() {
+    for (num, line) in s.lines().take(lines).enumerate() {
         table[num] = line
-            .trim()
-            .split_whitespace()
-            .map(|b| match b {
-                "0" => ' ',
-                "1" => '~',
-                _ => panic!("Erro ao ler o elemento: {} na linha {}", b, num),
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                '0' | '.' => ' ',
+                '1' | '#' | '~' => '~',
+                c => c,
             })
             .collect();
-        if let Some(col) = columns {
-            if col != table[num].len() {
-                panic!(
-                    "Linhas {} e {} possuem um número de colunas diferentes!",
-                    num - 1,
-                    num
-                );
-            }
+        if table[num].is_empty() {
+            return Err(format!("Linha {} vazia!", num + 1));
+        }
+        let (expected, found) = (table[0].len(), table[num].len());
+        if found != expected {
+            // A primeira coluna em que as linhas diferem: a que falta ou a primeira que sobra
+            return Err(format!(
+                "Linha {}, coluna {}: a linha tem {found} colunas, mas a linha 1 tem {expected}!",
+                num + 1,
+                found.min(expected) + 1
+            ));
         }
-        assert!(table[num].len() > 0, "Linha {} vazia!", num);
-        columns = Some(table[num].len());
     }
-    table
+    Ok(table)
+}
+
+/// Lê o tabuleiro da entrada padrão
+pub fn scan() -> Result<Table, String> {
+    let mut s = String::new();
+    use std::io::Read;
+    std::io::stdin()
+        .read_to_string(&mut s)
+        .map_err(|e| format!("Erro de leitura: {:?}", e))?;
+    parse(&s)
 }
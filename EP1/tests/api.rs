@@ -0,0 +1,49 @@
+//! Testes da interface da biblioteca (solver.rs), compilados e executados por `make api`
+
+extern crate pentaminos;
+
+use pentaminos::poliomino::generator;
+use pentaminos::solver::{self, Engine, Options};
+use pentaminos::table::{self, Table};
+
+/// Lê o tabuleiro do arquivo de testes
+fn board(path: &str) -> Table {
+    let s = std::fs::read_to_string(path).expect("Erro ao ler o tabuleiro");
+    table::parse(&s).unwrap()
+}
+
+/// Compara o total de `solver::count` com o esperado, com e sem a quebra de simetria
+fn check_count(path: &str, engine: Engine, expected: u64) -> bool {
+    let table = board(path);
+    let mut ok = true;
+    for break_symmetry in [false, true] {
+        let opts = Options {
+            engine,
+            break_symmetry,
+            ..Options::new(generator::generate())
+        };
+        let total = solver::count(&table, &opts).unwrap();
+        let desc = format!("solver::count em {path} (quebra de simetria: {break_symmetry})");
+        if total == expected {
+            println!("ok: {desc}");
+        } else {
+            println!("FALHOU: {desc}");
+            println!("  esperado: {expected}");
+            println!("  obtido:   {total}");
+            ok = false;
+        }
+    }
+    ok
+}
+
+fn main() {
+    let results = [
+        check_count("tests/12.in", Engine::Backtrack, 8),
+        check_count("tests/3.in", Engine::Bitboard, 1472),
+        check_count("tests/11.in", Engine::Backtrack, 0),
+    ];
+    if results.contains(&false) {
+        println!("Algum teste falhou");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,56 @@
+#!/usr/bin/env bash
+# Funções usadas pelos testes para comparar a saída do programa com a esperada. Cada teste
+# imprime "ok" ou "FALHOU" com as diferenças, e o script termina com `finish`, que sai com o
+# código 1 se algum teste falhou
+
+failed=0
+
+# check descrição esperado obtido
+check() {
+	if [ "$2" = "$3" ]; then
+		echo "ok: $1"
+	else
+		echo "FALHOU: $1"
+		echo "  esperado: $2"
+		echo "  obtido:   $3"
+		failed=1
+	fi
+}
+
+# check_error descrição mensagem comando...: o comando deve sair com código diferente de 0 e
+# imprimir uma linha contendo a mensagem
+check_error() {
+	local desc=$1 message=$2
+	shift 2
+	local output
+	output=$("$@" 2>&1)
+	local status=$?
+	if [ $status -ne 0 ] && grep -qF -- "$message" <<< "$output"; then
+		echo "ok: $desc"
+	else
+		echo "FALHOU: $desc"
+		echo "  esperado: erro com \"$message\""
+		echo "  obtido:   código $status, \"$output\""
+		failed=1
+	fi
+}
+
+# check_output descrição arquivo: compara a entrada padrão com a saída esperada, guardada no
+# arquivo (use `check_output ... < <(comandos)`, para que a falha seja registrada)
+check_output() {
+	local diff
+	if diff=$(diff -u "$2" - 2>&1); then
+		echo "ok: $1"
+	else
+		echo "FALHOU: $1"
+		echo "$diff"
+		failed=1
+	fi
+}
+
+finish() {
+	if [ $failed -ne 0 ]; then
+		echo "Algum teste falhou"
+	fi
+	exit $failed
+}
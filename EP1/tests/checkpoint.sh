@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+# Conta as soluções de cada tabuleiro parando a cada n nós e continuando do checkpoint; o total
+# deve ser o mesmo da contagem sem parar
+
+source tests/check.sh
+
+ck=$(mktemp)
+trap 'rm -f "$ck"' EXIT
+run() {
+	local resume="" stops=0 out
+	while true; do
+		out=$(./exec --count $resume --max-nodes $2 --checkpoint $ck < tests/$1.in 2>&1)
+		resume="--resume $ck"
+		grep -q "Checkpoint salvo" <<< "$out" || break
+		stops=$((stops + 1))
+	done
+	check "tests/$1.in parou ao menos uma vez" "sim" "$([ $stops -gt 0 ] && echo sim || echo não)"
+	check "tests/$1.in parando a cada $2 nós ($stops paradas)" "$(./exec --count < tests/$1.in)" "$out"
+}
+
+run 12 10000
+run 3 300000
+
+./exec --count --max-nodes 10000 --checkpoint $ck < tests/12.in > /dev/null 2>&1
+check_error "checkpoint de outro tabuleiro" "checkpoint de outro tabuleiro ou de outras peças" \
+	./exec --count --resume $ck < tests/3.in
+
+# Checkpoint do 5x8 com tetraminós, continuado com outro inventário ou outras opções
+tetrominoes="-c -p 4 -i I2,O2,T2,L2,S2"
+printf '5x8' | ./exec $tetrominoes --max-nodes 1000 --checkpoint $ck > /dev/null 2>&1
+check_error "checkpoint de outro inventário" "checkpoint de outro inventário" \
+	./exec -c -p 4 -i I,O2,T3,L2,S2 --resume $ck <<< "5x8"
+check_error "checkpoint continuado com --any" "checkpoint de outro inventário" \
+	./exec $tetrominoes --any --resume $ck <<< "5x8"
+check_error "checkpoint continuado com outra estratégia" "checkpoint de outras opções" \
+	./exec $tetrominoes --strategy mrv --resume $ck <<< "5x8"
+check "checkpoint continuado com as mesmas opções" "Soluções: 3106 (788 distintas)" \
+	"$(./exec $tetrominoes --resume $ck <<< "5x8")"
+sed -i 's/^options first/options nenhuma/' $ck
+check_error "checkpoint com estratégia inválida" "Checkpoint inválido: estratégia inválida" \
+	./exec $tetrominoes --resume $ck <<< "5x8"
+
+check "limite de soluções" \
+	"$(printf 'Busca parada antes do fim: limite de soluções\nSoluções: 3 (0 distintas)')" \
+	"$(./exec --count --max-solutions 3 < tests/12.in 2>&1)"
+
+finish
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Compara os motores backtrack, dlx e bitboard: o total de soluções deve ser igual, e o tempo de
+# cada um é impresso
+
+source tests/check.sh
+
+time=$(mktemp)
+trap 'rm -f "$time"' EXIT
+for i in {0..14}; do
+	echo Teste $i
+	declare -A out
+	for engine in backtrack dlx bitboard; do
+		out[$engine]=$(./exec --count --time --engine $engine < tests/$i.in 2> $time)
+		echo "$engine: ${out[$engine]} ($(cat $time))"
+	done
+	check "tests/$i.in: dlx igual ao backtrack" "${out[backtrack]}" "${out[dlx]}"
+	check "tests/$i.in: bitboard igual ao backtrack" "${out[backtrack]}" "${out[bitboard]}"
+done
+
+# O bitboard faz as mesmas podas do backtracking, então visita os mesmos nós, inclusive num
+# tabuleiro que já começa com uma região sem solução (a coluna da direita)
+nodes() {
+	./exec --count --nodes --engine $1 2>&1 > /dev/null
+}
+pocket=$(printf '%s#0\n' 0000000000000000000 0000000000000000000 0000000000000000000)
+check "nós do bitboard no tabuleiro com a região sem solução" \
+	"$(nodes backtrack <<< "$pocket")" "$(nodes bitboard <<< "$pocket")"
+for i in 2 9 10 11 14; do
+	check "nós do bitboard no tests/$i.in" \
+		"$(nodes backtrack < tests/$i.in)" "$(nodes bitboard < tests/$i.in)"
+done
+
+finish
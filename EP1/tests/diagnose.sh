@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Explica por que cada tabuleiro não tem solução (ou quantos nós a busca precisou), comparando
+# com a saída esperada em tests/diagnose.out
+
+source tests/check.sh
+
+run() {
+	echo "Teste $1 ($2)"
+	cat tests/impossiveis/$1.in
+	echo Output
+	./exec --diagnose $2 < tests/impossiveis/$1.in
+}
+
+diagnose() {
+	for i in {0..14}; do
+		echo "Teste $i: $(./exec --diagnose < tests/$i.in)"
+	done
+	run xadrez "--pieces 4"
+	run isolado
+	run forma "--inventory I2"
+}
+
+check_output "explicações de --diagnose" tests/diagnose.out < <(diagnose)
+
+finish
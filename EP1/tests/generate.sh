@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Gera tabuleiros com --generate, checando que têm solução (única com --unique), que a mesma
+# semente gera o mesmo tabuleiro e os erros de retângulo pequeno e inventário vazio
+
+source tests/check.sh
+
+board=$(mktemp)
+trap 'rm -f "$board"' EXIT
+
+check "grow com semente 1 e --unique gera o tests/14.in" \
+	"$(cat tests/14.in)" "$(./exec -g grow -u --seed 1)"
+check "a mesma semente gera o mesmo tabuleiro" \
+	"$(./exec -g carve --seed 7)" "$(./exec -g carve --seed 7)"
+
+for method in carve grow; do
+	for seed in 1 2 3; do
+		./exec -g $method --seed $seed > $board
+		check "$method com semente $seed tem solução" "sim" \
+			"$([ "$(./exec < $board)" != "-1" ] && echo sim || echo não)"
+		./exec -g $method --seed $seed -p 4 -i I,O,T,L,S -u > $board
+		check "$method com semente $seed e tetraminós tem solução única" "única" \
+			"$(./exec -p 4 -i I,O,T,L,S --unique < $board | sed -n 1p)"
+	done
+done
+
+check_error "retângulo pequeno" "O retângulo 3x3 tem menos que 60 quadrados" \
+	./exec -g carve --box 3x3
+check_error "inventário vazio com grow" "Inventário vazio" ./exec -g grow -i F0
+check_error "inventário vazio com carve" "Inventário vazio" ./exec -g carve -i F0
+
+finish
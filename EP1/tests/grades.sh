@@ -0,0 +1,54 @@
+#!/usr/bin/env bash
+# Grades hexagonal e triangular: checa o número de peças livres geradas com 1 a 7 células e as
+# soluções de tabuleiros conhecidos, inclusive as distintas por simetrias que não são as do
+# retângulo em volta do tabuleiro
+
+source tests/check.sh
+
+# pieces grade n total: o total de peças livres com n células é dado pela área das peças, que
+# --diagnose imprime num tabuleiro sem quadrados vazios
+pieces() {
+	check "$3 peças livres com $2 células na grade $1" \
+		"  - o tabuleiro tem 0 quadrados vazios, mas as peças têm $(($2 * $3)) quadrados" \
+		"$(./exec --diagnose --grid $1 -p $2 <<< '#' | sed -n 2p)"
+}
+
+n=1
+for total in 1 1 3 7 22 82 333; do
+	pieces hex $n $total
+	n=$((n + 1))
+done
+n=1
+for total in 1 1 1 3 4 12 24; do
+	pieces triangle $n $total
+	n=$((n + 1))
+done
+
+# Paralelogramo 9x4 dos hexiamantes: 74 soluções a menos do giro de 180°
+parallelogram=$(printf '%s\n' \
+	'#..................###' \
+	'##..................##' \
+	'###..................#' \
+	'####..................')
+check "hexiamantes no paralelogramo 9x4" "Soluções: 148 (74 distintas)" \
+	"$(./exec -c -e dlx --grid triangle -p 6 <<< "$parallelogram")"
+solution=$(mktemp)
+trap 'rm -f "$solution"' EXIT
+./exec -e dlx --grid triangle -p 6 <<< "$parallelogram" > $solution
+check "solução dos hexiamantes no paralelogramo 9x4" "Solução válida" \
+	"$(./exec --verify $solution --grid triangle -p 6 <<< "$parallelogram")"
+
+# Hexágono de lado 2 na grade triangular, com as 12 simetrias do hexágono
+hexagon=$(printf '%s\n' '##.....##' '#.......#' '#.......#' '##.....##')
+check "tetriamantes, 2 de cada, no hexágono" "Soluções: 6 (1 distintas)" \
+	"$(./exec -c --grid triangle -p 4 -i A2,B2,C2 <<< "$hexagon")"
+
+check "polihexes com 1, 2 e 3 células no 3x4" "Soluções: 112 (56 distintas)" \
+	"$(./exec -c --grid hex -p 1,2,3 <<< "3x4")"
+check "tetrahexes no 4x7" "Soluções: 18 (9 distintas)" \
+	"$(./exec -c --grid hex -p 4 <<< "4x7")"
+./exec --grid hex -p 4 <<< "4x7" > $solution
+check "solução dos tetrahexes no 4x7" "Solução válida" \
+	"$(./exec --verify $solution --grid hex -p 4 <<< "4x7")"
+
+finish
@@ -0,0 +1,52 @@
+#!/usr/bin/env bash
+# Lê as peças dos arquivos de tests/pecas com --piece-file, comparando com as peças geradas,
+# arquivos de peças inválidos e peças identificadas por caracteres que não são letras
+
+source tests/check.sh
+
+check "pentaminós do arquivo no tests/12.in" \
+	"$(./exec -c < tests/12.in)" \
+	"$(./exec -c -f tests/pecas/pentaminos.txt < tests/12.in)"
+check "tetraminós do arquivo, 2 de cada, no 5x8" \
+	"Soluções: 3106 (788 distintas)" \
+	"$(printf '5x8' | ./exec -c -f tests/pecas/tetraminos.txt -i I2,O2,T2,L2,S2)"
+check "tetraminós do arquivo no 4x5" \
+	"Soluções: 0 (0 distintas)" \
+	"$(printf '4x5' | ./exec -c -f tests/pecas/tetraminos.txt)"
+
+file=$(mktemp)
+trap 'rm -f "$file"' EXIT
+./exec -f tests/pecas/pentaminos.txt < tests/4.in > $file
+check "solução do tests/4.in com o arquivo, verificada com os pentaminós gerados" \
+	"Solução válida" \
+	"$(./exec --verify $file < tests/4.in)"
+printf 'A\n#.#\n' > $file
+check_error "peça não conexa" "Linha 1: peça A não é conexa" ./exec -f $file < tests/2.in
+printf 'A\n##\n\nA\n#\n' > $file
+check_error "peça repetida" "Linha 4: peça A repetida" ./exec -f $file < tests/2.in
+printf 'A\n#x\n' > $file
+check_error "caractere inválido" "Linha 2, coluna 2: caractere inválido 'x'" \
+	./exec -f $file < tests/2.in
+printf '// Só comentários\n' > $file
+check_error "arquivo sem peças" "Nenhuma peça encontrada" ./exec -f $file < tests/2.in
+check_error "arquivo inexistente" "Erro ao ler tests/pecas/nenhum.txt" \
+	./exec -f tests/pecas/nenhum.txt < tests/2.in
+
+# Peças que não são letras, já colocadas no tabuleiro
+printf '5\n###\n\n×\n##\n#.\n\n&\n##\n.#\n' > $file
+board='555\n××.\n×..\n'
+check "peças 5, × e & no tabuleiro" \
+	"$(printf '5 5 5 \n× × & \n× & & ')" \
+	"$(printf "$board" | ./exec -f $file)"
+solution=$(mktemp)
+printf "$board" | ./exec -f $file > $solution
+check "verificação com as peças 5, × e &" \
+	"Solução válida" \
+	"$(printf "$board" | ./exec -f $file --verify $solution)"
+rm -f $solution
+printf '0\n#\n' > $file
+check_error "identificador reservado" "esperado o identificador da peça" \
+	./exec -f $file < tests/2.in
+check_error "tabuleiro abreviado grande demais" "grande demais" ./exec <<< "100000x100000"
+
+finish
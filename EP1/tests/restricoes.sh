@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+# Conta as soluções com as restrições de tests/restricoes.txt e de restrições simples no
+# tests/12.in, e checa os erros de arquivos de restrições inválidos
+
+source tests/check.sh
+
+for engine in dlx bitboard; do
+	check "tests/restricoes.txt no tests/5.in ($engine)" \
+		"Soluções: 725 (725 distintas)" \
+		"$(./exec -c -e $engine -r tests/restricoes.txt < tests/5.in)"
+done
+
+file=$(mktemp)
+trap 'rm -f "$file"' EXIT
+echo "I rect 0,0 0,19" > $file
+check "I só na primeira linha do tests/12.in" \
+	"Soluções: 4 (4 distintas)" \
+	"$(./exec -c -r $file < tests/12.in)"
+echo "X cells 1,1 1,2 1,3 0,2 2,2" > $file
+check "X numa posição fixa do tests/12.in" \
+	"Soluções: 4 (4 distintas)" \
+	"$(./exec -c -r $file < tests/12.in)"
+echo "I orientations 1" > $file
+check "I só na vertical no tests/12.in" \
+	"Soluções: 0 (0 distintas)" \
+	"$(./exec -c -r $file < tests/12.in)"
+
+echo "Q rect 0,0 1,1" > $file
+check_error "peça inexistente" "Linha 1: peça Q não existe" ./exec -r $file < tests/5.in
+echo "X rect 0,0" > $file
+check_error "rect com um canto" "Linha 1: rect precisa de dois cantos" ./exec -r $file < tests/5.in
+echo "X circle 0,0" > $file
+check_error "restrição inválida" "Linha 1: restrição \"circle\" inválida" \
+	./exec -r $file < tests/5.in
+printf '// X\n\nX rect 0,0 9,9\n' > $file
+check_error "canto fora do tabuleiro" "Linha 3: argumento inválido \"9,9\"" \
+	./exec -r $file < tests/5.in
+echo "L orientations 9" > $file
+check_error "orientação inexistente" "Linha 1: argumento inválido \"9\"" \
+	./exec -r $file < tests/5.in
+check_error "arquivo inexistente" "Erro ao ler o arquivo de restrições tests/nenhum.txt" \
+	./exec -r tests/nenhum.txt < tests/5.in
+
+finish
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+# Empacotamento de policubos no espaço 3D: checa a primeira solução e o total de cada espaço e o
+# erro de um espaço grande demais
+
+source tests/check.sh
+
+# run espaço opções solução total
+run() {
+	check "solução de tests/espacos/$1.in ($2)" "$3" "$(./exec $2 < tests/espacos/$1.in)"
+	check "total de tests/espacos/$1.in ($2)" "$4" "$(./exec --count $2 < tests/espacos/$1.in)"
+}
+
+run soma --soma "$(printf '%s\n' \
+	'V V L   Z T L   T T T ' \
+	'Z V L   Z A A   P B B ' \
+	'Z A L   P A B   P P B ')" \
+	"Soluções: 11520 (480 distintas)"
+run tetracubos "--cubes 4" "$(printf '%s\n' \
+	'A A A A   B B F F ' \
+	'C C C E   B F F E ' \
+	'D C G E   B ~ ~ E ' \
+	'D D G G   D ~ ~ G ')" \
+	"Soluções: 130 (65 distintas)"
+run pentacubos --3d "$(printf '%s\n' \
+	'U V Z N N N T Y F F   U X N N W Y Y Y Y P ' \
+	'U V Z Z Z L T F F P   X X X W W L L L L P ' \
+	'U V V V Z T T T F P   U X W W I I I I I P ')" \
+	"Soluções: 96 (12 distintas)"
+
+check_error "espaço grande demais" \
+	"Espaço 1000x1000x1000 grande demais (no máximo 1048576 cubos)" \
+	./exec --3d <<< "1000x1000x1000"
+
+finish
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+# Compara as estratégias do backtracking nos tabuleiros dos testes que terminam em poucos
+# segundos: o total de soluções deve ser igual, e o número de nós mostra o tamanho da árvore de
+# busca de cada uma
+
+source tests/check.sh
+
+stats=$(mktemp)
+trap 'rm -f "$stats"' EXIT
+for i in 0 1 2 9 10 11 12 14; do
+	echo Teste $i
+	declare -A out
+	for strategy in first mrv rarest; do
+		out[$strategy]=$(./exec --count --strategy $strategy --nodes --time < tests/$i.in 2> $stats)
+		echo "$strategy: ${out[$strategy]} ($(tr '\n' ' ' < $stats | sed 's/ $//'))"
+	done
+	check "tests/$i.in: mrv igual a first" "${out[first]}" "${out[mrv]}"
+	check "tests/$i.in: rarest igual a first" "${out[first]}" "${out[rarest]}"
+done
+
+finish
@@ -0,0 +1,51 @@
+#!/usr/bin/env bash
+# Desenha em SVG a primeira solução de cada tabuleiro com solução, uma galeria com todas as
+# soluções do tests/12.in e tabuleiros nas outras grades e com bordas ligadas, checando o
+# número de peças desenhadas e de letras em cada arquivo
+
+source tests/check.sh
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+# summary arquivo: peças desenhadas, letras, soluções e se o arquivo termina em </svg>
+summary() {
+	echo "$(grep -c 'stroke="black"' $1) peças, $(grep -c '<text' $1) letras," \
+		"$(grep -c '<g transform' $1) soluções, $(tail -n 1 $1)"
+}
+
+for i in {0..14}; do
+	if [ "$(./exec < tests/$i.in)" = "-1" ]; then
+		check "tests/$i.in sem solução" \
+			"Nenhuma solução para desenhar em $dir/$i.svg" \
+			"$(./exec --svg $dir/$i.svg < tests/$i.in 2>&1 | head -n 1)"
+		check "tests/$i.in sem arquivo" "não" "$([ -e $dir/$i.svg ] && echo sim || echo não)"
+		continue
+	fi
+	./exec --svg $dir/$i.svg --labels < tests/$i.in > /dev/null
+	check "tests/$i.in" "12 peças, 12 letras, 1 soluções, </svg>" "$(summary $dir/$i.svg)"
+done
+
+run() {
+	./exec $2 --svg $dir/$1.svg > /dev/null
+	check "$1 ($2)" "$3" "$(summary $dir/$1.svg)"
+}
+
+run galeria "--all --columns 4 --labels" "96 peças, 96 letras, 8 soluções, </svg>" < tests/12.in
+run hexagonal "--grid hex --pieces 1,2,3 --labels" "5 peças, 5 letras, 1 soluções, </svg>" \
+	<<< "3x4"
+run triangular "--grid triangle --pieces 3 --inventory A4" \
+	"4 peças, 0 letras, 1 soluções, </svg>" <<< "2x6"
+run cilindro "--wrap lines --pieces 4 --inventory I2,O2,T2,L2,S2 --labels" \
+	"10 peças, 10 letras, 1 soluções, </svg>" <<< "5x8"
+
+# Letras que são caracteres especiais do XML
+pieces=$(mktemp)
+printf '<\n###\n\n&\n##\n#.\n\n"\n##\n.#\n' > $pieces
+./exec -f $pieces --svg $dir/xml.svg --labels <<< "3x3" > /dev/null
+rm -f $pieces
+check "letras <, & e \" escapadas" \
+	'>&lt;</text> >&amp;</text> >&quot;</text>' \
+	"$(grep -o '>[^<]*</text>' $dir/xml.svg | tr '\n' ' ' | sed 's/ $//')"
+
+finish
@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+# Lê tabuleiros desenhados e abreviados, checando que os erros de leitura indicam a linha e a
+# coluna
+
+source tests/check.sh
+
+check "abreviação 3x20" "Soluções: 8 (2 distintas)" "$(./exec -c <<< "3x20")"
+check "abreviação com buracos" "Soluções: 520 (65 distintas)" \
+	"$(./exec -c <<< "8x8 -holes 3,3 3,4 4,3 4,4")"
+check "desenho com espaços igual ao sem espaços" \
+	"$(./exec -c < tests/2.in)" "$(sed 's/./& /g' tests/2.in | ./exec -c)"
+
+check_error "entrada vazia" "Input vazio!" ./exec < /dev/null
+check_error "linha vazia" "Linha 2 vazia!" ./exec <<< $'000\n\n000'
+check_error "linha com colunas a menos" \
+	"Linha 2, coluna 3: a linha tem 2 colunas, mas a linha 1 tem 3!" ./exec <<< $'000\n00'
+check_error "linha com colunas a mais" \
+	"Linha 2, coluna 4: a linha tem 4 colunas, mas a linha 1 tem 3!" ./exec <<< $'000\n0000'
+check_error "peça que não existe" "linha 1, coluna 3: peça 2 não existe" \
+	./exec <<< $'0 0 2\n0 0 0'
+check_error "buraco fora do tabuleiro" "Erro na linha 1, coluna 13: buraco fora do tabuleiro \"9,0\"" \
+	./exec <<< "6x10 -holes 9,0"
+check_error "buraco inválido" "Erro na linha 2, coluna 1: buraco inválido \"a\"" \
+	./exec <<< $'6x10 -holes\na'
+check_error "abreviação sem -holes" "Erro na linha 1, coluna 6: esperava -holes, encontrou \"x\"" \
+	./exec <<< "6x10 x"
+
+finish
@@ -1,6 +1,6 @@
 #!/usr/bin/env bash
 
-for i in {0..11}; do
+for i in {0..14}; do
 	echo Teste $i
 	cat tests/$i.in
 	echo Output
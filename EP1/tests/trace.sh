@@ -0,0 +1,43 @@
+#!/usr/bin/env bash
+# Checa os passos escritos por --trace: a sequência completa num tabuleiro pequeno, os totais
+# de passos no 4x5 com tetraminós e a animação com --trace ansi
+
+source tests/check.sh
+
+check "passos do 2x5 com duas cópias do I" \
+	'I I I I I 
+I I I I I 
+{"event":"put","depth":1,"piece":"I","orientation":0,"pos":[0,0]}
+{"event":"put","depth":2,"piece":"I","orientation":0,"pos":[1,0]}
+{"event":"solution","depth":2,"board":["IIIII","IIIII"]}
+{"event":"remove","depth":2,"piece":"I","orientation":0,"pos":[1,0]}
+{"event":"fail","depth":2,"piece":"I","orientation":1,"pos":[1,0]}
+{"event":"pop","depth":2,"pos":[1,0]}
+{"event":"remove","depth":1,"piece":"I","orientation":0,"pos":[0,0]}
+{"event":"fail","depth":1,"piece":"I","orientation":1,"pos":[0,0]}
+{"event":"pop","depth":1,"pos":[0,0]}
+
+Soluções: 1 (1 distintas)' \
+	"$(printf '2x5' | ./exec -p 5 -i I2 -a --trace json 2>&1)"
+
+trace=$(mktemp)
+trap 'rm -f "$trace"' EXIT
+printf '4x5' | ./exec -c -n -p 4 -i I2,O2,T2,L2,S2 --any --trace json 2> $trace > /dev/null
+nodes=$(sed -n 's/^Nós: //p' $trace)
+check "um put por nó no 4x5" "$nodes" "$(grep -c '"event":"put"' $trace)"
+check "um remove por put no 4x5" "$nodes" "$(grep -c '"event":"remove"' $trace)"
+check "um passo solution por solução no 4x5" "270" "$(grep -c '"event":"solution"' $trace)"
+check "todas as linhas são passos" "0" \
+	"$(grep -v '^Nós: ' $trace | grep -vc '^{"event":"\(put\|fail\|prune\|remove\|pop\|solution\)","depth":[0-9]*,.*}$')"
+
+for i in 1 9 14; do
+	check "--trace não muda a solução do tests/$i.in" \
+		"$(./exec < tests/$i.in)" \
+		"$(./exec --trace json < tests/$i.in 2> /dev/null)"
+done
+
+ansi=$(printf '2x5' | ./exec -p 5 -i I2 --trace ansi --delay 0 2>&1 > /dev/null)
+check "--trace ansi limpa a tela e mostra o passo" "2" \
+	"$(grep -c $'\e\[H\e\[2JProfundidade [0-9]*: put I' <<< "$ansi")"
+
+finish
@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+# Verifica se a solução de cada tabuleiro é única (a menos das simetrias do tabuleiro)
+
+for i in {0..14}; do
+	echo "Teste $i: $(./exec --unique --engine dlx < tests/$i.in | sed -n 1p)"
+done;
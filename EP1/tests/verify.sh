@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+# Verifica as soluções impressas pelo programa para cada tabuleiro e soluções inválidas
+
+source tests/check.sh
+
+solution=$(mktemp)
+trap 'rm -f "$solution"' EXIT
+for i in {0..14}; do
+	./exec < tests/$i.in > $solution
+	if [ "$(cat $solution)" = "-1" ]; then
+		continue
+	fi
+	check "solução do tests/$i.in" "Solução válida" "$(./exec --verify $solution < tests/$i.in)"
+done
+
+check_output "tests/solucoes/2-invalida.txt" tests/solucoes/2-invalida.out \
+	< <(./exec --verify tests/solucoes/2-invalida.txt < tests/2.in)
+check_error "solução inválida sai com código 1" "Solução inválida:" \
+	./exec --verify tests/solucoes/2-invalida.txt < tests/2.in
+
+./exec < tests/3.in > $solution
+check_error "solução de outro tabuleiro" \
+	"o tabuleiro preenchido tem dimensões (4, 15), mas o tabuleiro dado tem (3, 20)" \
+	./exec --verify $solution < tests/2.in
+./exec < tests/2.in | sed '1s/^./Q/' > $solution
+check_error "peça inexistente" "a peça Q não existe (quadrados (0, 0))" \
+	./exec --verify $solution < tests/2.in
+
+finish
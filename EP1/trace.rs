@@ -0,0 +1,179 @@
+//! Rastreamento dos passos do backtracking, para acompanhar a busca (ex.: em aula ou ao
+//! procurar um erro).
+//!
+//! O [crate::backtrack] chama [Tracer::trace] a cada passo da sua pilha de estados. Sem
+//! rastreamento ele usa o [NoTrace], cujas chamadas são removidas pelo compilador, e a busca
+//! continua tão rápida quanto antes.
+
+use super::grid::Grid;
+use super::json::{self, Object};
+use super::poliomino::{Pieces, Placement};
+use super::table::{dim, Table, Topology};
+use std::io::Write;
+use std::time::Duration;
+
+/// Passo da busca
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// A peça foi colocada no tabuleiro (`try_put` teve sucesso)
+    Put(Placement),
+    /// A peça não cabe no tabuleiro nessa posição (`try_put` falhou)
+    Fail(Placement),
+    /// A peça foi tirada do tabuleiro, para tentar a próxima opção
+    Remove(Placement),
+    /// A peça foi colocada, mas alguma componente de quadrados vazios não pode ser preenchida
+    /// pelas peças restantes (`good_table` falhou), então a busca não desce
+    Prune(Placement),
+    /// Todas as opções do nível que cobre o quadrado acabaram e ele sai da pilha
+    Pop((i32, i32)),
+    /// O tabuleiro foi preenchido
+    Solution,
+}
+
+impl Event {
+    /// Nome do passo na saída
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Put(_) => "put",
+            Event::Fail(_) => "fail",
+            Event::Remove(_) => "remove",
+            Event::Prune(_) => "prune",
+            Event::Pop(_) => "pop",
+            Event::Solution => "solution",
+        }
+    }
+
+    /// Colocação envolvida no passo, se houver
+    pub fn placement(&self) -> Option<&Placement> {
+        match self {
+            Event::Put(p) | Event::Fail(p) | Event::Remove(p) | Event::Prune(p) => Some(p),
+            Event::Pop(_) | Event::Solution => None,
+        }
+    }
+}
+
+/// Recebe os passos da busca
+pub trait Tracer {
+    /// Chamado a cada passo, com o número de níveis na pilha da busca (`depth`) e o tabuleiro
+    /// logo depois do passo
+    fn trace(&mut self, event: Event, depth: usize, table: &Table);
+}
+
+/// Não rastreia nada
+pub struct NoTrace;
+
+impl Tracer for NoTrace {
+    #[inline(always)]
+    fn trace(&mut self, _: Event, _: usize, _: &Table) {}
+}
+
+/// Escreve cada passo como uma linha JSON. Ex.:
+/// `{"event":"put","depth":3,"piece":"F","orientation":2,"pos":[1,0]}`; no `pop` só há a posição
+/// do nível e no `solution`, o tabuleiro (como em [json::solution])
+pub struct JsonLines<W: Write> {
+    out: W,
+    pieces: Pieces,
+}
+
+impl<W: Write> JsonLines<W> {
+    pub fn new(out: W, pieces: Pieces) -> JsonLines<W> {
+        JsonLines { out, pieces }
+    }
+}
+
+impl<W: Write> Tracer for JsonLines<W> {
+    fn trace(&mut self, event: Event, depth: usize, table: &Table) {
+        let mut object = Object::new()
+            .field("event", &json::string(event.name()))
+            .field("depth", &depth.to_string());
+        match event {
+            Event::Pop(pos) => object = object.field("pos", &json::cell(pos)),
+            Event::Solution => object = object.field("board", &json::board(table)),
+            _ => {}
+        }
+        if let Some(p) = event.placement() {
+            let ident = self.pieces[p.p_idx][p.t_idx].ident;
+            object = object
+                .field("piece", &json::string(&ident.to_string()))
+                .field("orientation", &p.t_idx.to_string())
+                .field("pos", &json::cell(p.pos));
+        }
+        writeln!(self.out, "{}", object.finish()).expect("Erro ao escrever o rastreamento");
+    }
+}
+
+/// Animação no terminal: a cada passo limpa a tela, desenha o tabuleiro com os quadrados da
+/// peça do passo coloridos (verde ao colocar, vermelho se não coube, amarelo na poda e azul ao
+/// tirar) e espera `delay`
+pub struct Animation<W: Write> {
+    out: W,
+    pieces: Pieces,
+    topology: Topology,
+    delay: Duration,
+}
+
+impl<W: Write> Animation<W> {
+    pub fn new(out: W, pieces: Pieces, topology: Topology, delay: Duration) -> Animation<W> {
+        Animation {
+            out,
+            pieces,
+            topology,
+            delay,
+        }
+    }
+
+    /// Checa se o quadrado (x, y) é coberto pela colocação
+    fn covers(&self, p: &Placement, (x, y): (i32, i32), table: &Table) -> bool {
+        let t = &self.pieces[p.p_idx][p.t_idx];
+        let (px, py) = p.pos;
+        std::iter::once((0, 0))
+            .chain(t.pt.iter().copied())
+            .any(|(dx, dy)| self.topology.wrap((px + dx, py + dy), dim(table)) == Some((x, y)))
+    }
+}
+
+impl<W: Write> Tracer for Animation<W> {
+    fn trace(&mut self, event: Event, depth: usize, table: &Table) {
+        let mut frame = String::from("\x1b[H\x1b[2J");
+        frame += &format!("Profundidade {depth}: {}", event.name());
+        match event {
+            Event::Pop(pos) => frame += &format!(" {pos:?}"),
+            Event::Solution => {}
+            _ => {
+                let p = event.placement().unwrap();
+                let ident = self.pieces[p.p_idx][p.t_idx].ident;
+                frame += &format!(" {ident} (orientação {}) em {:?}", p.t_idx, p.pos);
+            }
+        }
+        frame.push('\n');
+        let color = match event {
+            Event::Put(_) => "32",
+            Event::Fail(_) => "31",
+            Event::Prune(_) => "33",
+            Event::Remove(_) => "34",
+            Event::Pop(_) | Event::Solution => "",
+        };
+        for (x, line) in table.iter().enumerate() {
+            if self.topology.grid == Grid::Hex {
+                frame += &" ".repeat(x);
+            }
+            for (y, &c) in line.iter().enumerate() {
+                let cell = (x as i32, y as i32);
+                let c = if c == ' ' { '.' } else { c };
+                match event.placement() {
+                    Some(p) if self.covers(p, cell, table) => {
+                        frame += &format!("\x1b[1;{color}m{c}\x1b[0m ");
+                    }
+                    _ => {
+                        frame.push(c);
+                        frame.push(' ');
+                    }
+                }
+            }
+            frame.push('\n');
+        }
+        write!(self.out, "{frame}").expect("Erro ao escrever a animação");
+        self.out.flush().expect("Erro ao escrever a animação");
+        std::thread::sleep(self.delay);
+    }
+}
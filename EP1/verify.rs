@@ -0,0 +1,265 @@
+//! Verificação de soluções feitas fora do programa (por outras ferramentas ou à mão): o
+//! tabuleiro preenchido deve cobrir os quadrados vazios do tabuleiro dado com cópias das peças.
+//!
+//! Cada grupo conexo de quadrados com a mesma letra deve ser exatamente coberto por cópias da
+//! peça com essa letra, em transformações geradas pelo [crate::poliomino::generator] (com uma
+//! cópia, o grupo deve ter a forma da peça); isso é verificado resolvendo o próprio grupo com o
+//! [solver], já que cópias vizinhas da mesma peça aparecem como um único grupo.
+
+use super::array;
+use super::inventory::Inventory;
+use super::pilha::Pilha;
+use super::poliomino::Pieces;
+use super::solver::{self, Options, SolveError};
+use super::table::{self, idx, Table, Topology};
+
+/// Problema na solução. As posições são (linha, coluna), a partir de 0
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// O tabuleiro preenchido não tem as dimensões do tabuleiro dado
+    Dimensions {
+        board: (i32, i32),
+        filled: (i32, i32),
+    },
+    /// O quadrado vazio do tabuleiro não foi coberto
+    Uncovered { cell: (i32, i32) },
+    /// O quadrado não pode ser ocupado, mas foi coberto pela peça
+    Blocked { cell: (i32, i32), piece: char },
+    /// O quadrado já estava ocupado pela peça `expected` no tabuleiro dado, mas está com `found`
+    Moved {
+        cell: (i32, i32),
+        expected: char,
+        found: char,
+    },
+    /// Os quadrados têm uma letra que não é de nenhuma peça
+    UnknownPiece {
+        piece: char,
+        cells: Box<[(i32, i32)]>,
+    },
+    /// O grupo conexo de quadrados com a letra da peça não é coberto por cópias dela
+    Shape {
+        piece: char,
+        cells: Box<[(i32, i32)]>,
+    },
+    /// A peça cobre `cells` quadrados, mas o inventário pede `copies` cópias (no máximo, se
+    /// nem todas as peças precisam ser usadas)
+    Count {
+        piece: char,
+        cells: usize,
+        copies: usize,
+        exact: bool,
+    },
+}
+
+/// Valor inicial dos elementos da [Pilha]
+impl Default for Violation {
+    fn default() -> Violation {
+        Violation::Uncovered { cell: (0, 0) }
+    }
+}
+
+/// Lista de quadrados (ex.: "(0, 1) (0, 2)")
+fn cell_list(cells: &[(i32, i32)]) -> String {
+    let cells: Box<[String]> = cells.iter().map(|c| format!("{c:?}")).collect();
+    cells.join(" ")
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Violation::Dimensions { board, filled } => write!(
+                f,
+                "o tabuleiro preenchido tem dimensões {filled:?}, mas o tabuleiro dado tem \
+                 {board:?}"
+            ),
+            Violation::Uncovered { cell } => write!(f, "o quadrado {cell:?} não foi coberto"),
+            Violation::Blocked { cell, piece } => write!(
+                f,
+                "o quadrado {cell:?} não pode ser ocupado, mas foi coberto pela peça {piece}"
+            ),
+            Violation::Moved {
+                cell,
+                expected,
+                found,
+            } => write!(
+                f,
+                "o quadrado {cell:?} já estava ocupado pela peça {expected}, mas está com {found}"
+            ),
+            Violation::UnknownPiece { piece, cells } => write!(
+                f,
+                "a peça {piece} não existe (quadrados {})",
+                cell_list(cells)
+            ),
+            Violation::Shape { piece, cells } => write!(
+                f,
+                "os quadrados {} não formam a peça {piece} (ou cópias dela)",
+                cell_list(cells)
+            ),
+            Violation::Count {
+                piece,
+                cells,
+                copies,
+                exact,
+            } => write!(
+                f,
+                "a peça {piece} cobre {cells} quadrados, mas deve ser usada {}{copies} {}",
+                if *exact { "" } else { "no máximo " },
+                if *copies == 1 { "vez" } else { "vezes" }
+            ),
+        }
+    }
+}
+
+/// Verifica se `filled` é uma solução de `board` com as peças, o inventário e a topologia de
+/// `opts` (as restrições não são verificadas). Devolve os problemas encontrados, ou nenhum se a
+/// solução é válida
+pub fn verify(
+    board: &Table,
+    filled: &Table,
+    opts: &Options,
+) -> Result<Box<[Violation]>, SolveError> {
+    solver::validate(board)?;
+    solver::validate(filled)?;
+    let mut violations = Pilha::new();
+    let dim = table::dim(board);
+    if table::dim(filled) != dim {
+        violations.push(Violation::Dimensions {
+            board: dim,
+            filled: table::dim(filled),
+        });
+        return Ok(violations.to_array());
+    }
+    let pieces = &opts.pieces;
+    let (lin, col) = dim;
+    for x in 0..lin {
+        for y in 0..col {
+            let cell = (x, y);
+            match (board[idx(x)][idx(y)], filled[idx(x)][idx(y)]) {
+                ('~', ' ' | '~') => {}
+                (_, ' ' | '~') => violations.push(Violation::Uncovered { cell }),
+                (' ', _) => {}
+                ('~', piece) => violations.push(Violation::Blocked { cell, piece }),
+                (expected, found) if expected != found => violations.push(Violation::Moved {
+                    cell,
+                    expected,
+                    found,
+                }),
+                _ => {}
+            }
+        }
+    }
+    groups(filled, pieces, opts.topology, &mut violations)?;
+
+    let inventory = match &opts.inventory {
+        Some(inventory) => inventory.clone(),
+        None => Inventory::all(pieces, true),
+    };
+    for (p, &copies) in pieces.iter().zip(inventory.counts.iter()) {
+        let piece = p[0].ident;
+        let cells = filled
+            .iter()
+            .flat_map(|line| line.iter())
+            .filter(|&&c| c == piece)
+            .count();
+        let used = cells.div_ceil(p[0].size());
+        if used > copies || (inventory.exact && used < copies) {
+            violations.push(Violation::Count {
+                piece,
+                cells,
+                copies,
+                exact: inventory.exact,
+            });
+        }
+    }
+    Ok(violations.to_array())
+}
+
+/// Verifica cada grupo conexo de quadrados com a mesma letra no tabuleiro preenchido
+fn groups(
+    filled: &Table,
+    pieces: &Pieces,
+    topology: Topology,
+    violations: &mut Pilha<Violation>,
+) -> Result<(), SolveError> {
+    let (lin, col) = table::dim(filled);
+    let mut seen = array::new::<Box<[bool]>>(idx(lin));
+    for line in seen.iter_mut() {
+        *line = array::new(idx(col));
+    }
+    for x in 0..lin {
+        for y in 0..col {
+            let piece = filled[idx(x)][idx(y)];
+            if piece == ' ' || piece == '~' || seen[idx(x)][idx(y)] {
+                continue;
+            }
+            let mut group: Pilha<(i32, i32)> = Pilha::new();
+            let mut stack = Pilha::new();
+            stack.push((x, y));
+            seen[idx(x)][idx(y)] = true;
+            while !stack.empty() {
+                let (cx, cy) = *stack.back();
+                stack.pop();
+                group.push((cx, cy));
+                for &(dx, dy) in topology.grid.neighbours((cx, cy)) {
+                    let Some((px, py)) = topology.wrap((cx + dx, cy + dy), (lin, col)) else {
+                        continue;
+                    };
+                    if filled[idx(px)][idx(py)] == piece && !seen[idx(px)][idx(py)] {
+                        seen[idx(px)][idx(py)] = true;
+                        stack.push((px, py));
+                    }
+                }
+            }
+            let mut cells = group.to_array();
+            cells.sort();
+            let Some(p_idx) = pieces.iter().position(|p| p[0].ident == piece) else {
+                violations.push(Violation::UnknownPiece { piece, cells });
+                continue;
+            };
+            if !covered(filled, pieces, p_idx, &cells, topology)? {
+                violations.push(Violation::Shape { piece, cells });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checa se os quadrados podem ser cobertos exatamente por cópias da peça p_idx
+fn covered(
+    filled: &Table,
+    pieces: &Pieces,
+    p_idx: usize,
+    cells: &[(i32, i32)],
+    topology: Topology,
+) -> Result<bool, SolveError> {
+    let size = pieces[p_idx][0].size();
+    if !cells.len().is_multiple_of(size) {
+        return Ok(false);
+    }
+    // Tabuleiro com apenas os quadrados do grupo vazios
+    let mut table: Table = array::new(filled.len());
+    for line in table.iter_mut() {
+        *line = array::new::<char>(filled[0].len());
+        for c in line.iter_mut() {
+            *c = '~';
+        }
+    }
+    for &(x, y) in cells.iter() {
+        table[idx(x)][idx(y)] = ' ';
+    }
+    let mut counts = array::new::<usize>(pieces.len());
+    counts[p_idx] = cells.len() / size;
+    let opts = Options {
+        inventory: Some(Inventory {
+            counts,
+            exact: true,
+        }),
+        topology,
+        ..Options::new(pieces.clone())
+    };
+    match solver::solve(&table, &opts) {
+        Ok(_) => Ok(true),
+        Err(SolveError::NoTiling) => Ok(false),
+        Err(e) => Err(e),
+    }
+}